@@ -0,0 +1,195 @@
+//! Compares day 3's item sets with the hash sets they replaced, on large
+//! generated inputs, and times repacking a puzzle-sized input. Run with
+//! `cargo bench --bench rucksacks`.
+
+use std::{collections::HashSet, hint::black_box, time::Instant};
+
+use advent_of_code_2022_rust::{
+    bench::Summary,
+    day3::{
+        groups::{badge_priorities, GROUP_SIZE},
+        packing_errors_priority,
+        priorities::PriorityTable,
+        read_rucksacks_contents, Priority, Rucksack, SharedBy,
+    },
+    diagnostics::Diagnostics,
+};
+
+#[path = "../src/test_util.rs"]
+mod test_util;
+
+use test_util::Xorshift;
+
+const GROUPS: usize = 50_000;
+const COMPARTMENT_SIZE: usize = 24;
+const ITERATIONS: usize = 10;
+
+/// Rucksacks and compartments of the repacking input, as big as a puzzle
+/// input split into the most compartments `rucksacks --repack` is used with.
+const REPACK_RUCKSACKS: usize = 300;
+const REPACK_COMPARTMENTS: usize = 6;
+const REPACK_COMPARTMENT_SIZE: usize = 8;
+
+/// Groups of rucksacks sharing exactly one badge, each sack drawing its
+/// other items from a pool no other sack of the group uses.
+fn generate_input() -> String {
+    let items: Vec<char> = ('a'..='z').chain('A'..='Z').collect();
+    let mut random = Xorshift::new(0x2022_0003);
+    let mut next = move |bound: usize| random.below(bound);
+
+    let mut input = String::new();
+    for _ in 0..GROUPS {
+        let badge = items[next(items.len())];
+        let others: Vec<char> = items
+            .iter()
+            .copied()
+            .filter(|&item| item != badge)
+            .collect();
+        for pool in others.chunks(others.len() / GROUP_SIZE).take(GROUP_SIZE) {
+            let mut line: Vec<char> = (0..COMPARTMENT_SIZE * 2 - 1)
+                .map(|_| pool[next(pool.len())])
+                .collect();
+            line.insert(next(line.len()), badge);
+            input.extend(line);
+            input.push('\n');
+        }
+    }
+    input
+}
+
+/// Rucksacks of items drawn at random from the whole alphabet.
+fn generate_repack_input() -> String {
+    let items: Vec<char> = ('a'..='z').chain('A'..='Z').collect();
+    let mut random = Xorshift::new(0x2022_0006);
+
+    let mut input = String::new();
+    for _ in 0..REPACK_RUCKSACKS {
+        input.extend(
+            (0..REPACK_COMPARTMENTS * REPACK_COMPARTMENT_SIZE)
+                .map(|_| items[random.below(items.len())]),
+        );
+        input.push('\n');
+    }
+    input
+}
+
+/// Day 3 part 1 as it was solved with a hash set per compartment.
+fn hash_set_packing_errors(rucksacks: &[Rucksack]) -> u64 {
+    rucksacks
+        .iter()
+        .map(|rucksack| {
+            let sets: Vec<HashSet<char>> = rucksack
+                .compartments
+                .iter()
+                .map(|compartment| compartment.contents.iter().copied().collect())
+                .collect();
+            let mut errors: HashSet<char> = HashSet::new();
+            for (index, set) in sets.iter().enumerate() {
+                for item in set {
+                    if sets[index + 1..].iter().any(|other| other.contains(item)) {
+                        errors.insert(*item);
+                    }
+                }
+            }
+            errors
+                .iter()
+                .filter_map(char::to_priority)
+                .map(u64::from)
+                .sum::<u64>()
+        })
+        .sum()
+}
+
+/// Day 3 part 2 with hash set intersections.
+fn hash_set_badges(rucksacks: &[Rucksack]) -> u64 {
+    rucksacks
+        .chunks_exact(GROUP_SIZE)
+        .map(|group| {
+            group
+                .iter()
+                .map(|rucksack| rucksack.items().collect::<HashSet<char>>())
+                .reduce(|common, items| common.intersection(&items).copied().collect())
+                .unwrap_or_default()
+                .iter()
+                .filter_map(char::to_priority)
+                .map(u64::from)
+                .sum::<u64>()
+        })
+        .sum()
+}
+
+fn time(name: &str, solve: impl Fn() -> u64) -> (u64, Summary) {
+    let mut samples = Vec::with_capacity(ITERATIONS);
+    let mut answer = 0;
+    for _ in 0..ITERATIONS {
+        let start = Instant::now();
+        answer = black_box(solve());
+        samples.push(start.elapsed());
+    }
+
+    let summary = Summary::of(&mut samples).unwrap();
+    println!(
+        "{name:<24} {:>10.3} ms median, {:>10.3} ms min",
+        summary.median.as_secs_f64() * 1000.0,
+        summary.min.as_secs_f64() * 1000.0
+    );
+    (answer, summary)
+}
+
+fn compare(name: &str, hash_sets: impl Fn() -> u64, item_sets: impl Fn() -> u64) {
+    let (expected, before) = time(&format!("{name}, hash sets"), hash_sets);
+    let (actual, after) = time(&format!("{name}, item sets"), item_sets);
+
+    assert_eq!(actual, expected, "{name} answers differ");
+    println!(
+        "{name}: {:.1}x faster",
+        before.median.as_secs_f64() / after.median.as_secs_f64()
+    );
+}
+
+fn main() {
+    let input = generate_input();
+    let rucksacks = read_rucksacks_contents(
+        &input,
+        2,
+        &PriorityTable::default(),
+        &mut Diagnostics::default(),
+    )
+    .unwrap();
+    println!(
+        "{} rucksacks, {ITERATIONS} iterations each",
+        rucksacks.len()
+    );
+
+    compare(
+        "Packing errors",
+        || hash_set_packing_errors(&rucksacks),
+        || {
+            packing_errors_priority(&PriorityTable::default(), &rucksacks, SharedBy::AtLeastTwo)
+                .unwrap()
+        },
+    );
+    compare(
+        "Badges",
+        || hash_set_badges(&rucksacks),
+        || badge_priorities(&PriorityTable::default(), &rucksacks, GROUP_SIZE).unwrap(),
+    );
+
+    let rucksacks = read_rucksacks_contents(
+        &generate_repack_input(),
+        REPACK_COMPARTMENTS,
+        &PriorityTable::default(),
+        &mut Diagnostics::default(),
+    )
+    .unwrap();
+    let (moved_items, _) = time(
+        &format!("Repacking, {REPACK_COMPARTMENTS} compartments"),
+        || {
+            rucksacks
+                .iter()
+                .map(|rucksack| rucksack.repacking().unwrap().moved_items() as u64)
+                .sum()
+        },
+    );
+    println!("{moved_items} items moved");
+}
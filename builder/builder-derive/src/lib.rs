@@ -5,7 +5,7 @@ use syn::{parse_macro_input, DeriveInput, Data, Field};
 
 #[proc_macro_derive(Builder)]
 pub fn derive_builder(input: TokenStream) -> TokenStream {
-    let DeriveInput { ident, data, .. } = parse_macro_input!(input as DeriveInput);
+    let DeriveInput { ident, vis, data, .. } = parse_macro_input!(input as DeriveInput);
 
     let builder_ident = format_ident!("{}Builder", ident);
 
@@ -25,7 +25,7 @@ pub fn derive_builder(input: TokenStream) -> TokenStream {
 
     let builder_field_setters = fields.iter().map(|Field { ident, ty, .. }| {
         quote! {
-            fn #ident(mut self, #ident: #ty) -> Self {
+            #vis fn #ident(mut self, #ident: #ty) -> Self {
                 self.#ident = Some(#ident);
                 self
             }
@@ -49,7 +49,7 @@ pub fn derive_builder(input: TokenStream) -> TokenStream {
             }
         }
 
-        struct #builder_ident {
+        #vis struct #builder_ident {
             #(#builder_fields,)*
         }
 
@@ -0,0 +1,183 @@
+use std::time::Duration;
+
+use crate::{
+    diagnostics::Diagnostics,
+    error::{Error, Result},
+    solution::{Day, Part},
+};
+
+/// Spread of the times taken by one step over several iterations.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Summary {
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+}
+
+impl Summary {
+    /// Summarises `samples`, or `None` if there are none.
+    pub fn of(samples: &mut [Duration]) -> Option<Summary> {
+        samples.sort();
+
+        let middle = samples.len() / 2;
+        let median = match samples.len() {
+            0 => return None,
+            len if len.is_multiple_of(2) => (samples[middle - 1] + samples[middle]) / 2,
+            _ => samples[middle],
+        };
+
+        Some(Summary {
+            min: samples[0],
+            median,
+            max: samples[samples.len() - 1],
+        })
+    }
+}
+
+/// Timings of a day's parse and parts. An unsolved part has none.
+#[derive(Debug)]
+pub struct DayBench {
+    pub day: u8,
+    pub iterations: usize,
+    pub parse: Summary,
+    pub part1: Option<Summary>,
+    pub part2: Option<Summary>,
+}
+
+/// Parses `input` and answers both parts of `day` `iterations` times, timing
+/// each step separately. Only the first iteration's warnings are kept in
+/// `diagnostics`, the others would repeat them.
+pub fn bench_day(
+    day: &Day,
+    input: &str,
+    iterations: usize,
+    diagnostics: &mut Diagnostics,
+) -> Result<DayBench> {
+    if iterations == 0 {
+        return Err(Error::validation("Benchmarks need at least one iteration"));
+    }
+
+    let mut parse_samples = Vec::with_capacity(iterations);
+    let mut part1_samples = Vec::with_capacity(iterations);
+    let mut part2_samples = Vec::with_capacity(iterations);
+
+    for iteration in 0..iterations {
+        let mut iteration_diagnostics = Diagnostics::new(diagnostics.mode);
+        let solved = day
+            .solver
+            .solve(input, &Part::ALL, &mut iteration_diagnostics)?;
+        if iteration == 0 {
+            diagnostics.warnings = iteration_diagnostics.warnings;
+        }
+
+        parse_samples.push(solved.parse_elapsed);
+        for part_answer in solved.answers {
+            let samples = match part_answer.part {
+                Part::One => &mut part1_samples,
+                Part::Two => &mut part2_samples,
+            };
+            match part_answer.answer {
+                Ok(_) => samples.push(part_answer.elapsed),
+                Err(Error::Unsolved) => (),
+                Err(error) => return Err(error),
+            }
+        }
+    }
+
+    Ok(DayBench {
+        day: day.number,
+        iterations,
+        parse: Summary::of(&mut parse_samples).unwrap(),
+        part1: Summary::of(&mut part1_samples),
+        part2: Summary::of(&mut part2_samples),
+    })
+}
+
+fn summary_json(summary: Option<Summary>) -> String {
+    match summary {
+        Some(summary) => format!(
+            "{{\"min_ns\":{},\"median_ns\":{},\"max_ns\":{}}}",
+            summary.min.as_nanos(),
+            summary.median.as_nanos(),
+            summary.max.as_nanos()
+        ),
+        None => "null".to_string(),
+    }
+}
+
+/// Renders `benches` as a JSON array with one object per day, times in
+/// nanoseconds and `null` for unsolved parts.
+pub fn to_json(benches: &[DayBench]) -> String {
+    let days: Vec<String> = benches
+        .iter()
+        .map(|bench| {
+            format!(
+                "{{\"day\":{},\"iterations\":{},\"parse\":{},\"part1\":{},\"part2\":{}}}",
+                bench.day,
+                bench.iterations,
+                summary_json(Some(bench.parse)),
+                summary_json(bench.part1),
+                summary_json(bench.part2)
+            )
+        })
+        .collect();
+
+    format!("[{}]", days.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::find_day;
+
+    fn millis(samples: &[u64]) -> Vec<Duration> {
+        samples
+            .iter()
+            .map(|&ms| Duration::from_millis(ms))
+            .collect()
+    }
+
+    #[test]
+    fn summary_takes_the_middle_sample_or_the_mean_of_the_middle_two() {
+        let odd = Summary::of(&mut millis(&[5, 1, 3])).unwrap();
+        assert_eq!(odd.min, Duration::from_millis(1));
+        assert_eq!(odd.median, Duration::from_millis(3));
+        assert_eq!(odd.max, Duration::from_millis(5));
+
+        let even = Summary::of(&mut millis(&[4, 1, 2, 8])).unwrap();
+        assert_eq!(even.median, Duration::from_millis(3));
+
+        assert_eq!(Summary::of(&mut []), None);
+    }
+
+    #[test]
+    fn benches_solved_parts_only() {
+        let day = find_day(4).unwrap();
+        let bench = bench_day(day, "2-4,6-8\n", 3, &mut Diagnostics::default()).unwrap();
+
+        assert_eq!(bench.iterations, 3);
+        assert!(bench.part1.is_some());
+        assert!(bench.part2.is_none());
+        assert!(bench_day(day, "2-4,6-8\n", 0, &mut Diagnostics::default()).is_err());
+    }
+
+    #[test]
+    fn json_has_one_object_per_day() {
+        let summary = Summary::of(&mut millis(&[1])).unwrap();
+        let bench = DayBench {
+            day: 4,
+            iterations: 1,
+            parse: summary,
+            part1: Some(summary),
+            part2: None,
+        };
+
+        assert_eq!(
+            to_json(&[bench]),
+            "[{\"day\":4,\"iterations\":1,\
+             \"parse\":{\"min_ns\":1000000,\"median_ns\":1000000,\"max_ns\":1000000},\
+             \"part1\":{\"min_ns\":1000000,\"median_ns\":1000000,\"max_ns\":1000000},\
+             \"part2\":null}]"
+        );
+    }
+}
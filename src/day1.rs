@@ -1,61 +1,469 @@
-use std::{fs::read_to_string, path::Path};
+use std::{cmp::Reverse, collections::BinaryHeap, io::BufRead};
 
-struct Elf {
-    food_items: Vec<FoodItem>,
+use crate::{
+    diagnostics::Diagnostics,
+    error::{Error, Result},
+    input::InputSource,
+    solution::Solution,
+};
+
+pub mod stats;
+
+pub struct Elf {
+    /// 1-based position of the elf in the input.
+    pub id: usize,
+    pub food_items: Vec<FoodItem>,
+}
+
+pub struct FoodItem {
+    pub calories: u32,
 }
 
-struct FoodItem {
-    calories: i32,
+/// Adds `calories` to a running `total`, failing rather than wrapping if the
+/// total no longer fits.
+fn add_calories(total: u64, calories: u64) -> Result<u64> {
+    total
+        .checked_add(calories)
+        .ok_or_else(|| Error::validation("Calorie total does not fit in 64 bits"))
 }
 
 impl Elf {
-    fn total_food_calories(&self) -> i32 {
-        self.food_items
+    pub fn total_food_calories(&self) -> Result<u64> {
+        self.food_items.iter().try_fold(0, |total, food_item| {
+            add_calories(total, u64::from(food_item.calories))
+        })
+    }
+}
+
+/// An elf's [`Elf::id`] and the calories it carries.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RankedElf {
+    pub id: usize,
+    pub total_calories: u64,
+}
+
+/// Ranking of elves by the calories they carry.
+pub trait CalorieRanking {
+    /// Every elf, most calories first. Elves carrying the same calories keep
+    /// their input order.
+    fn ranked(&self) -> Result<Vec<RankedElf>>;
+
+    /// The first `n` elves of [`CalorieRanking::ranked`], or all of them if
+    /// there are fewer.
+    fn top(&self, n: usize) -> Result<Vec<RankedElf>> {
+        let mut ranked = self.ranked()?;
+        ranked.truncate(n);
+        Ok(ranked)
+    }
+
+    /// Calories carried by the `n` elves carrying the most. Fails if there
+    /// are fewer than `n` elves.
+    fn top_total_calories(&self, n: usize) -> Result<u64> {
+        let top = self.top(n)?;
+        if top.len() < n {
+            return Err(Error::validation(format!(
+                "There are {} elves, fewer than {n}",
+                top.len()
+            )));
+        }
+        top.iter()
+            .try_fold(0, |total, elf| add_calories(total, elf.total_calories))
+    }
+}
+
+impl CalorieRanking for [Elf] {
+    fn ranked(&self) -> Result<Vec<RankedElf>> {
+        let mut ranked = self
             .iter()
-            .fold(0, |total, food_item| total + food_item.calories)
+            .map(|elf| {
+                Ok(RankedElf {
+                    id: elf.id,
+                    total_calories: elf.total_food_calories()?,
+                })
+            })
+            .collect::<Result<Vec<RankedElf>>>()?;
+
+        ranked.sort_by(|a, b| {
+            b.total_calories
+                .cmp(&a.total_calories)
+                .then(a.id.cmp(&b.id))
+        });
+        Ok(ranked)
+    }
+}
+
+/// Reads the calories on `line`, the input's 0-based `line_index`. Only
+/// plain digits are accepted, so signs and negative values are rejected.
+fn read_calories(line_index: usize, line: &str) -> Result<u32> {
+    let error = |message: &str| Error::parse(line_index + 1, line, message);
+
+    if let Some(digits) = line.strip_prefix('-') {
+        if !digits.is_empty() && digits.chars().all(|char| char.is_ascii_digit()) {
+            return Err(error("Calories cannot be negative"));
+        }
+    }
+    if line.is_empty() || !line.chars().all(|char| char.is_ascii_digit()) {
+        return Err(error("Calories are not a number"));
     }
+    line.parse::<u32>()
+        .map_err(|_| error("Calories are out of range"))
+}
+
+/// How blank lines group food items into elves.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BlankLines {
+    /// A run of blank lines separates two elves. Blank lines before the first
+    /// or after the last elf are ignored.
+    #[default]
+    Collapse,
+    /// Every blank line ends an elf, so an elf carrying nothing is written as
+    /// an extra blank line. A blank line at the end of the input only ends
+    /// the last elf.
+    EmptyElves,
 }
 
-fn read_elf_calorie_list(path: &Path) -> Vec<Elf> {
+pub fn read_elf_calorie_list(
+    input: &str,
+    blank_lines: BlankLines,
+    diagnostics: &mut Diagnostics,
+) -> Result<Vec<Elf>> {
     let mut elves: Vec<Elf> = Vec::new();
-    let mut elf_index = 0;
-    for line in read_to_string(path).unwrap_or_default().lines() {
-        let elf = match elves.get_mut(elf_index) {
-            Some(elf) => elf,
-            None => {
-                elves.push(Elf {
-                    food_items: Vec::new(),
-                });
-                elves.get_mut(elf_index).unwrap()
-            }
-        };
+    // Whether the last elf in `elves` can still take food items.
+    let mut elf_open = false;
 
+    for (line_index, line) in input.lines().enumerate() {
         match line {
-            "" => elf_index += 1,
-            line => {
-                if let Ok(calories) = line.parse::<i32>() {
-                    elf.food_items.push(FoodItem { calories })
+            "" => {
+                if !elf_open && blank_lines == BlankLines::EmptyElves {
+                    elves.push(Elf {
+                        id: elves.len() + 1,
+                        food_items: Vec::new(),
+                    });
                 }
+                elf_open = false;
             }
+            line => match read_calories(line_index, line) {
+                Ok(calories) => {
+                    if !elf_open {
+                        elves.push(Elf {
+                            id: elves.len() + 1,
+                            food_items: Vec::new(),
+                        });
+                        elf_open = true;
+                    }
+                    let elf = elves.last_mut().unwrap();
+                    elf.food_items.push(FoodItem { calories });
+                }
+                Err(error) => diagnostics.reject(error)?,
+            },
         }
     }
 
-    elves
+    Ok(elves)
 }
 
-pub fn day1() {
-    let elves = read_elf_calorie_list(Path::new("elf-calorie-list.txt"));
-    let largest_calories_elf =
-        elves
-            .iter()
-            .enumerate()
-            .fold((0, 0), |largest_calories_elf, elf| {
-                let total_elf_calories = elf.1.total_food_calories();
-                if total_elf_calories > largest_calories_elf.1 {
-                    return (elf.0, total_elf_calories);
+/// Calorie totals of an inventory read without keeping its food items.
+#[derive(Debug, PartialEq, Eq)]
+pub struct CalorieTally {
+    pub elf_count: usize,
+    /// The elves carrying the most calories, ranked as by
+    /// [`CalorieRanking::ranked`].
+    pub top: Vec<RankedElf>,
+}
+
+/// Reads an inventory from `reader` line by line, keeping only the running
+/// total of the current elf and the `top_n` elves carrying the most calories,
+/// so memory does not grow with the input. Elves are grouped as by
+/// [`read_elf_calorie_list`]. `source` names the input in read errors. In
+/// lenient mode give `diagnostics` a warning limit, or every rejected line
+/// is kept.
+pub fn tally_elf_calories(
+    mut reader: impl BufRead,
+    source: &InputSource,
+    blank_lines: BlankLines,
+    top_n: usize,
+    diagnostics: &mut Diagnostics,
+) -> Result<CalorieTally> {
+    // Min-heap on (calories, later id first) so the elf to drop is on top.
+    // Grown as elves are kept, since `top_n` may be far more than there are.
+    let mut top: BinaryHeap<Reverse<(u64, Reverse<usize>)>> = BinaryHeap::new();
+    let mut keep = |id: usize, total_calories: u64| {
+        top.push(Reverse((total_calories, Reverse(id))));
+        if top.len() > top_n {
+            top.pop();
+        }
+    };
+
+    let mut elf_count = 0;
+    let mut current_elf: Option<(usize, u64)> = None;
+    let mut line = String::new();
+
+    for line_index in 0.. {
+        line.clear();
+        let read = reader.read_line(&mut line).map_err(|error| Error::Io {
+            source: source.clone(),
+            error,
+        })?;
+        if read == 0 {
+            break;
+        }
+
+        match line.trim_end_matches(['\n', '\r']) {
+            "" => match current_elf.take() {
+                Some((id, total_calories)) => keep(id, total_calories),
+                None if blank_lines == BlankLines::EmptyElves => {
+                    elf_count += 1;
+                    keep(elf_count, 0);
                 }
-                largest_calories_elf
-            });
+                None => (),
+            },
+            text => match read_calories(line_index, text) {
+                Ok(calories) => {
+                    let (_, total_calories) = current_elf.get_or_insert_with(|| {
+                        elf_count += 1;
+                        (elf_count, 0)
+                    });
+                    *total_calories = add_calories(*total_calories, u64::from(calories))?;
+                }
+                Err(error) => diagnostics.reject(error)?,
+            },
+        }
+    }
+
+    if let Some((id, total_calories)) = current_elf {
+        keep(id, total_calories);
+    }
+
+    let top = top
+        .into_sorted_vec()
+        .into_iter()
+        .map(|Reverse((total_calories, Reverse(id)))| RankedElf { id, total_calories })
+        .collect();
+
+    Ok(CalorieTally { elf_count, top })
+}
+
+pub struct Day1;
+
+impl Solution for Day1 {
+    type Puzzle = Vec<Elf>;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(input: &str, diagnostics: &mut Diagnostics) -> Result<Vec<Elf>> {
+        read_elf_calorie_list(input, BlankLines::default(), diagnostics)
+    }
 
-    print!("{:?}", largest_calories_elf)
+    fn part1(elves: &Vec<Elf>) -> Result<u64> {
+        elves.top_total_calories(1)
+    }
+
+    fn part2(elves: &Vec<Elf>) -> Result<u64> {
+        elves.top_total_calories(3)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        diagnostics::ParseMode,
+        solution::{solve_example, Part},
+    };
+
+    const EXAMPLE: &str = "\
+1000
+2000
+3000
+
+4000
+
+5000
+6000
+
+7000
+8000
+9000
+
+10000
+";
+
+    #[test]
+    fn reads_one_elf_per_blank_line_separated_group() {
+        let elves =
+            read_elf_calorie_list(EXAMPLE, BlankLines::default(), &mut Diagnostics::default())
+                .unwrap();
+
+        let totals: Vec<u64> = elves
+            .iter()
+            .map(|elf| elf.total_food_calories().unwrap())
+            .collect();
+        assert_eq!(totals, [6000, 4000, 11000, 24000, 10000]);
+    }
+
+    #[test]
+    fn lenient_mode_skips_lines_that_are_not_numbers() {
+        let mut diagnostics = Diagnostics::new(ParseMode::Lenient);
+        let elves =
+            read_elf_calorie_list("100\nabc\n200\n", BlankLines::default(), &mut diagnostics)
+                .unwrap();
+
+        assert_eq!(elves[0].total_food_calories().unwrap(), 300);
+        assert_eq!(diagnostics.warnings.len(), 1);
+        assert!(read_elf_calorie_list(
+            "100\nabc\n",
+            BlankLines::default(),
+            &mut Diagnostics::default()
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn tally_matches_the_ranking_of_the_full_list() {
+        let elves =
+            read_elf_calorie_list(EXAMPLE, BlankLines::default(), &mut Diagnostics::default())
+                .unwrap();
+        let tally = tally_elf_calories(
+            EXAMPLE.as_bytes(),
+            &InputSource::Stdin,
+            BlankLines::default(),
+            3,
+            &mut Diagnostics::default(),
+        )
+        .unwrap();
+
+        assert_eq!(tally.elf_count, elves.len());
+        assert_eq!(tally.top, elves.top(3).unwrap());
+    }
+
+    #[test]
+    fn tally_keeps_at_most_top_n_elves_preferring_earlier_ties() {
+        let input = "500\n\n300\n\n500\n\n200\n";
+        let tally = tally_elf_calories(
+            input.as_bytes(),
+            &InputSource::Stdin,
+            BlankLines::default(),
+            2,
+            &mut Diagnostics::default(),
+        )
+        .unwrap();
+
+        assert_eq!(tally.elf_count, 4);
+        let top: Vec<usize> = tally.top.iter().map(|elf| elf.id).collect();
+        assert_eq!(top, [1, 3]);
+    }
+
+    #[test]
+    fn tally_of_more_elves_than_there_are_keeps_them_all() {
+        let tally = tally_elf_calories(
+            EXAMPLE.as_bytes(),
+            &InputSource::Stdin,
+            BlankLines::default(),
+            usize::MAX,
+            &mut Diagnostics::default(),
+        )
+        .unwrap();
+
+        assert_eq!(tally.top.len(), 5);
+    }
+
+    #[test]
+    fn tally_keeps_warnings_up_to_the_limit() {
+        let input = "100\nabc\n".repeat(10);
+        let mut diagnostics = Diagnostics::new(ParseMode::Lenient).with_warning_limit(3);
+        let tally = tally_elf_calories(
+            input.as_bytes(),
+            &InputSource::Stdin,
+            BlankLines::default(),
+            1,
+            &mut diagnostics,
+        )
+        .unwrap();
+
+        assert_eq!(tally.top[0].total_calories, 1000);
+        assert_eq!(diagnostics.warnings.len(), 3);
+        assert_eq!(diagnostics.dropped_warnings, 7);
+    }
+
+    #[test]
+    fn blank_line_runs_collapse_or_become_empty_elves() {
+        let input = "\n100\n\n\n\n200\n\n";
+        let read = |blank_lines| {
+            let elves =
+                read_elf_calorie_list(input, blank_lines, &mut Diagnostics::default()).unwrap();
+            let tally = tally_elf_calories(
+                input.as_bytes(),
+                &InputSource::Stdin,
+                blank_lines,
+                10,
+                &mut Diagnostics::default(),
+            )
+            .unwrap();
+            assert_eq!(tally.top, elves.ranked().unwrap());
+
+            elves
+                .iter()
+                .map(|elf| (elf.id, elf.total_food_calories().unwrap()))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(read(BlankLines::Collapse), [(1, 100), (2, 200)]);
+        assert_eq!(
+            read(BlankLines::EmptyElves),
+            [(1, 0), (2, 100), (3, 0), (4, 0), (5, 200)]
+        );
+    }
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(solve_example(&Day1, EXAMPLE, Part::One), "24000");
+    }
+
+    #[test]
+    fn ranks_ties_in_input_order() {
+        let elves = read_elf_calorie_list(
+            "300\n\n500\n\n200\n100\n\n500\n",
+            BlankLines::default(),
+            &mut Diagnostics::default(),
+        )
+        .unwrap();
+
+        let top: Vec<usize> = elves.top(3).unwrap().iter().map(|elf| elf.id).collect();
+        assert_eq!(top, [2, 4, 1]);
+        assert_eq!(elves.top(10).unwrap().len(), 4);
+        assert_eq!(elves.top_total_calories(2).unwrap(), 1000);
+        assert!(elves.top_total_calories(5).is_err());
+    }
+
+    #[test]
+    fn rejects_negative_and_out_of_range_calories() {
+        let message = |input: &str| match read_elf_calorie_list(
+            input,
+            BlankLines::default(),
+            &mut Diagnostics::default(),
+        ) {
+            Err(Error::Parse(parse_error)) => parse_error.message,
+            _ => panic!("expected a parse error for {input:?}"),
+        };
+
+        assert_eq!(message("-100\n"), "Calories cannot be negative");
+        assert_eq!(message("+100\n"), "Calories are not a number");
+        assert_eq!(message("4294967296\n"), "Calories are out of range");
+    }
+
+    #[test]
+    fn totals_beyond_32_bits_do_not_wrap() {
+        let input = "4294967295\n4294967295\n";
+        let elves =
+            read_elf_calorie_list(input, BlankLines::default(), &mut Diagnostics::default())
+                .unwrap();
+
+        assert_eq!(elves[0].total_food_calories().unwrap(), 8589934590);
+        assert!(add_calories(u64::MAX, 1).is_err());
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(solve_example(&Day1, EXAMPLE, Part::Two), "45000");
+    }
 }
@@ -0,0 +1,234 @@
+use super::Elf;
+use crate::error::Result;
+
+/// Percentiles of elf totals reported by [`distribution`].
+pub const PERCENTILES: [u8; 5] = [10, 25, 50, 75, 90];
+
+/// Summary of the food items one elf carries. Min, max, mean and median are
+/// `None` for an elf with no items.
+#[derive(Debug, PartialEq)]
+pub struct ItemStats {
+    pub elf_id: usize,
+    pub item_count: usize,
+    pub total_calories: u64,
+    pub min: Option<u32>,
+    pub max: Option<u32>,
+    pub mean: Option<f64>,
+    pub median: Option<f64>,
+}
+
+impl Elf {
+    pub fn stats(&self) -> Result<ItemStats> {
+        let mut calories: Vec<u32> = self
+            .food_items
+            .iter()
+            .map(|food_item| food_item.calories)
+            .collect();
+        calories.sort();
+
+        let item_count = calories.len();
+        let mean = match item_count {
+            0 => None,
+            _ => Some(
+                calories
+                    .iter()
+                    .map(|&calories| f64::from(calories))
+                    .sum::<f64>()
+                    / item_count as f64,
+            ),
+        };
+        let median = match item_count {
+            0 => None,
+            count if count.is_multiple_of(2) => {
+                Some((f64::from(calories[count / 2 - 1]) + f64::from(calories[count / 2])) / 2.0)
+            }
+            count => Some(f64::from(calories[count / 2])),
+        };
+
+        Ok(ItemStats {
+            elf_id: self.id,
+            item_count,
+            total_calories: self.total_food_calories()?,
+            min: calories.first().copied(),
+            max: calories.last().copied(),
+            mean,
+            median,
+        })
+    }
+}
+
+/// Elves whose totals fall in `start..=end`.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Bucket {
+    pub start: u64,
+    pub end: u64,
+    pub elf_count: usize,
+}
+
+/// How elf totals spread across the whole inventory.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Distribution {
+    pub elf_count: usize,
+    /// Equal width buckets from the smallest to the largest total.
+    pub histogram: Vec<Bucket>,
+    /// Nearest rank value of each of [`PERCENTILES`].
+    pub percentiles: Vec<(u8, u64)>,
+}
+
+/// Spread of elf totals over at most `bucket_count` buckets, or `None` if
+/// there are no elves or no buckets.
+pub fn distribution(elves: &[Elf], bucket_count: usize) -> Result<Option<Distribution>> {
+    let mut totals = elves
+        .iter()
+        .map(Elf::total_food_calories)
+        .collect::<Result<Vec<u64>>>()?;
+    totals.sort();
+
+    let (min, max) = match (totals.first(), totals.last()) {
+        (Some(&min), Some(&max)) if bucket_count > 0 => (min, max),
+        _ => return Ok(None),
+    };
+
+    let width = (max - min) / bucket_count as u64 + 1;
+    let histogram = (0..bucket_count as u64)
+        .map_while(|bucket| min.checked_add(bucket * width))
+        .take_while(|&start| start <= max)
+        .map(|start| {
+            let end = start.saturating_add(width - 1).min(max);
+            Bucket {
+                start,
+                end,
+                elf_count: totals
+                    .iter()
+                    .filter(|&total| (start..=end).contains(total))
+                    .count(),
+            }
+        })
+        .collect();
+
+    let percentiles = PERCENTILES
+        .iter()
+        .map(|&percentile| {
+            let rank = (usize::from(percentile) * totals.len())
+                .div_ceil(100)
+                .max(1);
+            (percentile, totals[rank - 1])
+        })
+        .collect();
+
+    Ok(Some(Distribution {
+        elf_count: totals.len(),
+        histogram,
+        percentiles,
+    }))
+}
+
+fn option_json<T: ToString>(value: Option<T>) -> String {
+    value.map_or_else(|| "null".to_string(), |value| value.to_string())
+}
+
+/// Renders per elf `stats` and the `distribution` as one JSON object.
+pub fn to_json(stats: &[ItemStats], distribution: Option<&Distribution>) -> String {
+    let elves: Vec<String> = stats
+        .iter()
+        .map(|stats| {
+            format!(
+                "{{\"elf\":{},\"items\":{},\"total\":{},\"min\":{},\"max\":{},\"mean\":{},\"median\":{}}}",
+                stats.elf_id,
+                stats.item_count,
+                stats.total_calories,
+                option_json(stats.min),
+                option_json(stats.max),
+                option_json(stats.mean),
+                option_json(stats.median)
+            )
+        })
+        .collect();
+
+    let distribution = match distribution {
+        Some(distribution) => {
+            let histogram: Vec<String> = distribution
+                .histogram
+                .iter()
+                .map(|bucket| {
+                    format!(
+                        "{{\"start\":{},\"end\":{},\"elves\":{}}}",
+                        bucket.start, bucket.end, bucket.elf_count
+                    )
+                })
+                .collect();
+            let percentiles: Vec<String> = distribution
+                .percentiles
+                .iter()
+                .map(|(percentile, total)| format!("\"p{percentile}\":{total}"))
+                .collect();
+            format!(
+                "{{\"elves\":{},\"histogram\":[{}],\"percentiles\":{{{}}}}}",
+                distribution.elf_count,
+                histogram.join(","),
+                percentiles.join(",")
+            )
+        }
+        None => "null".to_string(),
+    };
+
+    format!(
+        "{{\"elves\":[{}],\"distribution\":{}}}",
+        elves.join(","),
+        distribution
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        day1::{read_elf_calorie_list, BlankLines},
+        diagnostics::Diagnostics,
+    };
+
+    fn elves(input: &str) -> Vec<Elf> {
+        read_elf_calorie_list(input, BlankLines::default(), &mut Diagnostics::default()).unwrap()
+    }
+
+    #[test]
+    fn item_stats() {
+        let stats = elves("4000\n1000\n2000\n3000\n")[0].stats().unwrap();
+
+        assert_eq!(stats.item_count, 4);
+        assert_eq!(stats.total_calories, 10000);
+        assert_eq!((stats.min, stats.max), (Some(1000), Some(4000)));
+        assert_eq!(stats.mean, Some(2500.0));
+        assert_eq!(stats.median, Some(2500.0));
+
+        let empty = Elf {
+            id: 1,
+            food_items: Vec::new(),
+        }
+        .stats()
+        .unwrap();
+        assert_eq!((empty.item_count, empty.min, empty.median), (0, None, None));
+    }
+
+    #[test]
+    fn distribution_buckets_and_percentiles() {
+        let elves = elves("100\n\n200\n\n300\n\n400\n\n1000\n");
+        let distribution = distribution(&elves, 3).unwrap().unwrap();
+
+        let buckets: Vec<(u64, u64, usize)> = distribution
+            .histogram
+            .iter()
+            .map(|bucket| (bucket.start, bucket.end, bucket.elf_count))
+            .collect();
+        assert_eq!(buckets, [(100, 400, 4), (401, 701, 0), (702, 1000, 1)]);
+        assert_eq!(
+            distribution.percentiles,
+            [(10, 100), (25, 200), (50, 300), (75, 400), (90, 1000)]
+        );
+    }
+
+    #[test]
+    fn no_distribution_without_elves() {
+        assert_eq!(distribution(&[], 10).unwrap(), None);
+    }
+}
@@ -1,130 +1,336 @@
-use std::{cmp::Ordering, fs::read_to_string, path::Path};
-
-#[derive(Eq, PartialEq, PartialOrd)]
-enum RpcAttack {
-    Rock,
-    Paper,
-    Scissors,
-    Forfeit,
-}
+use std::cmp::Ordering;
 
-impl RpcAttack {
-    fn from_char(char: Option<char>) -> RpcAttack {
-        use RpcAttack::*;
-
-        match char {
-            None => Forfeit,
-            Some(char) => match char {
-                'A' | 'X' => Rock,
-                'B' | 'Y' => Paper,
-                'C' | 'Z' => Scissors,
-                _ => Forfeit,
-            },
-        }
-    }
+use crate::{
+    diagnostics::Diagnostics,
+    error::{Error, Result},
+    solution::Solution,
+};
 
-    fn value(&self) -> i32 {
-        use RpcAttack::*;
+use self::{
+    optimal::best_reply,
+    rules::{Rules, Shape},
+    scoring::{score_round, score_rounds, Scores},
+};
+
+pub mod optimal;
+pub mod rules;
+pub mod scoring;
+pub mod tournament;
+
+/// Result of a round for the player reading the strategy guide.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RpcOutcome {
+    Lose,
+    Draw,
+    Win,
+}
+
+impl RpcOutcome {
+    /// How the player's shape compares with the opponent's.
+    pub fn ordering(&self) -> Ordering {
+        use RpcOutcome::*;
 
         match self {
-            Rock => 1,
-            Paper => 2,
-            Scissors => 3,
-            _ => 0,
+            Lose => Ordering::Less,
+            Draw => Ordering::Equal,
+            Win => Ordering::Greater,
         }
     }
 }
 
-impl Ord for RpcAttack {
-    fn cmp(&self, other: &Self) -> Ordering {
-        use Ordering::*;
-        use RpcAttack::*;
-
-        match (self, other) {
-            (Rock, Paper) => Less,
-            (Rock, Scissors) => Greater,
-            (Rock, Rock) => Equal,
-            (Paper, Scissors) => Less,
-            (Paper, Rock) => Greater,
-            (Paper, Paper) => Equal,
-            (Scissors, Rock) => Less,
-            (Scissors, Paper) => Greater,
-            (Scissors, Scissors) => Equal,
-            (_, Forfeit) => Greater,
-            (Forfeit, _) => Less,
-        }
-    }
+/// How the second column of the strategy guide is read.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Strategy {
+    /// The letter is the shape to play, `X`, `Y` and `Z` for rock, paper or
+    /// scissors unless the rules say otherwise.
+    #[default]
+    Shapes,
+    /// The letter is the outcome to reach, `X`, `Y` and `Z` for lose, draw or
+    /// win unless the rules say otherwise.
+    Outcomes,
+    /// The letter is ignored and the reply scoring the most is played.
+    Optimal,
 }
 
-struct RpcRound {
-    p1: RpcAttack,
-    p2: RpcAttack,
+#[derive(Debug)]
+pub struct RpcRound {
+    pub p1: Shape,
+    pub p2: Shape,
 }
 
 impl RpcRound {
-    fn get_points(&self) -> (i32, i32) {
-        let mut p1_value = self.p1.value();
-        let mut p2_value = self.p1.value();
-
-        let tie_value = 3;
-        let win_value = 6;
-
-        if self.p1 == self.p2 {
-            p1_value += tie_value;
-            p2_value += tie_value;
-        } else if self.p1 > self.p2 {
-            p1_value += win_value;
-        } else {
-            p2_value += win_value;
-        }
+    pub fn get_points(&self, rules: &Rules) -> (i32, i32) {
+        let score = score_round(rules, self);
+
+        (score.p1(), score.p2())
+    }
+}
+
+/// A line of the strategy guide: the opponent's shape and the letter in the
+/// second column, whose meaning depends on the [`Strategy`].
+#[derive(Debug)]
+pub struct RpcGuideLine {
+    pub p1: Shape,
+    pub p2_letter: char,
+}
+
+impl RpcGuideLine {
+    pub fn round(&self, rules: &Rules, strategy: Strategy) -> Result<RpcRound> {
+        let p2 = match strategy {
+            Strategy::Shapes => rules
+                .player_shape(self.p2_letter)
+                .ok_or_else(|| Error::validation(format!("{} is not a shape", self.p2_letter)))?,
+            Strategy::Outcomes => {
+                let outcome = rules.outcome(self.p2_letter).ok_or_else(|| {
+                    Error::validation(format!("{} is not an outcome", self.p2_letter))
+                })?;
+                rules.shape_for_outcome(self.p1, outcome).ok_or_else(|| {
+                    Error::validation(format!(
+                        "No shape reaches {outcome:?} against {}",
+                        rules.name(self.p1)
+                    ))
+                })?
+            }
+            Strategy::Optimal => best_reply(rules, self.p1),
+        };
 
-        (p1_value, p2_value)
+        Ok(RpcRound { p1: self.p1, p2 })
     }
 }
 
-struct Rpc {
-    rounds: Vec<RpcRound>,
+#[derive(Debug)]
+pub struct Rpc {
+    pub rules: Rules,
+    pub guide: Vec<RpcGuideLine>,
 }
 
 impl Rpc {
-    fn calculate_scores(&self) -> (i32, i32) {
-        let mut p1_score = 0;
-        let mut p2_score = 0;
-        for round in self.rounds.iter() {
-            let points = round.get_points();
-            p1_score += points.0;
-            p2_score += points.1;
+    pub fn rounds(&self, strategy: Strategy) -> Result<Vec<RpcRound>> {
+        self.guide
+            .iter()
+            .enumerate()
+            .map(|(index, guide_line)| {
+                guide_line
+                    .round(&self.rules, strategy)
+                    .map_err(|error| match error {
+                        Error::Validation(message) => {
+                            Error::validation(format!("Round {}: {message}", index + 1))
+                        }
+                        error => error,
+                    })
+            })
+            .collect()
+    }
+
+    /// Scores of every round played with `strategy`, and both players'
+    /// totals.
+    pub fn calculate_scores(&self, strategy: Strategy) -> Result<Scores> {
+        Ok(score_rounds(&self.rules, &self.rounds(strategy)?))
+    }
+}
+
+fn letter_list(letters: &[char]) -> String {
+    match letters {
+        [] => String::new(),
+        [letter] => letter.to_string(),
+        [rest @ .., last] => {
+            let rest: Vec<String> = rest.iter().map(char::to_string).collect();
+            format!("{} or {last}", rest.join(", "))
+        }
+    }
+}
+
+fn read_rpc_guide_line(rules: &Rules, line_index: usize, line: &str) -> Result<RpcGuideLine> {
+    let chars: Vec<char> = line.chars().collect();
+    let (p1, p2) = match chars.as_slice() {
+        [p1, ' ', p2] => (*p1, *p2),
+        _ => {
+            return Err(Error::parse(
+                line_index + 1,
+                line,
+                "Expected a round like \"A Y\"",
+            ))
         }
+    };
 
-        (p1_score, p2_score)
+    let p1 = match rules.opponent_shape(p1) {
+        Some(shape) => shape,
+        None => {
+            let letters: Vec<char> = rules
+                .shapes
+                .iter()
+                .map(|shape| shape.opponent_letter)
+                .collect();
+            return Err(Error::parse(
+                line_index + 1,
+                line,
+                format!("Expected {} for player 1", letter_list(&letters)),
+            )
+            .at(1, 1));
+        }
+    };
+    if rules.player_shape(p2).is_none() && rules.outcome(p2).is_none() {
+        let mut letters: Vec<char> = rules
+            .shapes
+            .iter()
+            .map(|shape| shape.player_letter)
+            .chain(rules.outcome_letters)
+            .collect();
+        letters.sort();
+        letters.dedup();
+        return Err(Error::parse(
+            line_index + 1,
+            line,
+            format!("Expected {} for player 2", letter_list(&letters)),
+        )
+        .at(3, 1));
     }
+
+    Ok(RpcGuideLine { p1, p2_letter: p2 })
 }
 
-fn read_rpc_stategy_guide(path: &Path) -> Rpc {
-    let mut rpc = Rpc { rounds: Vec::new() };
-
-    for line in read_to_string(path).unwrap_or_default().lines() {
-        let mut p1: Option<char> = None;
-        let mut p2: Option<char> = None;
-        for char in line.chars().enumerate() {
-            match char {
-                char if char.0 == 0 => p1 = Some(char.1),
-                char if char.0 == 2 => p2 = Some(char.1),
-                char if char.0 == 3 => break,
-                _ => (),
-            }
+/// Reads a strategy guide whose letters follow `rules`.
+pub fn read_rpc_guide_with_rules(
+    input: &str,
+    rules: Rules,
+    diagnostics: &mut Diagnostics,
+) -> Result<Rpc> {
+    let mut guide = Vec::new();
+
+    for (line_index, line) in input.lines().enumerate() {
+        match read_rpc_guide_line(&rules, line_index, line) {
+            Ok(guide_line) => guide.push(guide_line),
+            Err(error) => diagnostics.reject(error)?,
         }
-        rpc.rounds.push(RpcRound {
-            p1: RpcAttack::from_char(p1),
-            p2: RpcAttack::from_char(p2),
-        });
     }
 
-    rpc
+    Ok(Rpc { rules, guide })
+}
+
+pub fn read_rpc_stategy_guide(input: &str, diagnostics: &mut Diagnostics) -> Result<Rpc> {
+    read_rpc_guide_with_rules(input, Rules::default(), diagnostics)
+}
+
+pub struct Day2;
+
+impl Solution for Day2 {
+    type Puzzle = Rpc;
+    type Answer1 = i32;
+    type Answer2 = i32;
+
+    fn parse(input: &str, diagnostics: &mut Diagnostics) -> Result<Rpc> {
+        read_rpc_stategy_guide(input, diagnostics)
+    }
+
+    fn part1(rpc: &Rpc) -> Result<i32> {
+        Ok(rpc.calculate_scores(Strategy::Shapes)?.p2_total)
+    }
+
+    fn part2(rpc: &Rpc) -> Result<i32> {
+        Ok(rpc.calculate_scores(Strategy::Outcomes)?.p2_total)
+    }
 }
 
-pub fn day2() {
-    let rpc = read_rpc_stategy_guide(&Path::new("rock-paper-scissors-strategy-guide.txt"));
-    let scores = rpc.calculate_scores();
-    print!("{:?}", scores);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::{solve_example, Part};
+
+    const EXAMPLE: &str = "\
+A Y
+B X
+C Z
+";
+
+    #[test]
+    fn reads_one_round_per_line() {
+        let rpc = read_rpc_stategy_guide(EXAMPLE, &mut Diagnostics::default()).unwrap();
+
+        assert_eq!(rpc.guide.len(), 3);
+        assert_eq!(rpc.rules.name(rpc.guide[0].p1), "Rock");
+        assert_eq!(rpc.guide[0].p2_letter, 'Y');
+    }
+
+    fn shape_names(rpc: &Rpc, strategy: Strategy) -> Vec<&str> {
+        rpc.rounds(strategy)
+            .unwrap()
+            .iter()
+            .map(|round| rpc.rules.name(round.p2))
+            .collect()
+    }
+
+    #[test]
+    fn second_column_is_a_shape_or_an_outcome() {
+        let rpc = read_rpc_stategy_guide(EXAMPLE, &mut Diagnostics::default()).unwrap();
+
+        assert_eq!(
+            shape_names(&rpc, Strategy::Shapes),
+            ["Paper", "Rock", "Scissors"]
+        );
+        assert_eq!(
+            shape_names(&rpc, Strategy::Outcomes),
+            ["Rock", "Rock", "Rock"]
+        );
+        assert_eq!(
+            shape_names(&rpc, Strategy::Optimal),
+            ["Paper", "Scissors", "Rock"]
+        );
+    }
+
+    #[test]
+    fn shape_for_outcome() {
+        for rules in [
+            Rules::rock_paper_scissors(),
+            Rules::rock_paper_scissors_lizard_spock(),
+            Rules::cyclic(7).unwrap(),
+        ] {
+            for shape in rules.all_shapes() {
+                for outcome in [RpcOutcome::Lose, RpcOutcome::Draw, RpcOutcome::Win] {
+                    let reply = rules.shape_for_outcome(shape, outcome).unwrap();
+                    assert_eq!(rules.compare(reply, shape), outcome.ordering());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn letters_follow_the_rules() {
+        let mut rules = Rules::rock_paper_scissors_lizard_spock();
+        rules.outcome_letters = ['L', 'D', 'W'];
+        let rpc =
+            read_rpc_guide_with_rules("E V\nA W\n", rules, &mut Diagnostics::default()).unwrap();
+
+        assert_eq!(shape_names(&rpc, Strategy::Shapes), ["Rock", "Paper"]);
+        assert_eq!(
+            rpc.calculate_scores(Strategy::Shapes).unwrap().p2_total,
+            1 + 2 + 6
+        );
+        assert!(rpc.rounds(Strategy::Outcomes).is_err());
+
+        let rpc = read_rpc_guide_with_rules("C W\nC D\n", rpc.rules, &mut Diagnostics::default())
+            .unwrap();
+        let rounds = rpc.rounds(Strategy::Outcomes).unwrap();
+        assert!(rpc.rules.beats(rounds[0].p2, rounds[0].p1));
+        assert_eq!(rounds[1].p2, rounds[1].p1);
+    }
+
+    #[test]
+    fn rejects_letters_outside_the_guide() {
+        let error = read_rpc_stategy_guide("A Y\nD X\n", &mut Diagnostics::default()).unwrap_err();
+
+        match error {
+            Error::Parse(parse_error) => assert_eq!((parse_error.line, parse_error.column), (2, 1)),
+            error => panic!("expected a parse error, got {error:?}"),
+        }
+    }
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(solve_example(&Day2, EXAMPLE, Part::One), "15");
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(solve_example(&Day2, EXAMPLE, Part::Two), "12");
+    }
 }
@@ -0,0 +1,130 @@
+use std::cmp::Reverse;
+
+use super::{
+    rules::{Rules, Shape},
+    scoring::score_round,
+    Rpc, RpcRound,
+};
+
+/// Points the player scores replying `reply` to `opponent`.
+fn reply_points(rules: &Rules, opponent: Shape, reply: Shape) -> i32 {
+    score_round(
+        rules,
+        &RpcRound {
+            p1: opponent,
+            p2: reply,
+        },
+    )
+    .p2()
+}
+
+/// The reply scoring the most against `opponent`, the first shape of the
+/// rules on ties.
+pub fn best_reply(rules: &Rules, opponent: Shape) -> Shape {
+    rules
+        .all_shapes()
+        .max_by_key(|&reply| (reply_points(rules, opponent, reply), Reverse(reply)))
+        .unwrap()
+}
+
+/// What the player can score against a known sequence of opponent shapes.
+#[derive(Debug, PartialEq)]
+pub struct Analysis {
+    /// The replies scoring the most in every round.
+    pub best_replies: Vec<Shape>,
+    pub best_total: i32,
+    /// The total of a random responder that always picks the worst reply.
+    pub worst_total: i32,
+    /// The mean total of a responder picking every shape with the same
+    /// chance.
+    pub expected_total: f64,
+}
+
+/// Analyses the replies to `opponents`. Rounds are scored independently, so
+/// the best sequence is the best reply to each round.
+pub fn analyse(rules: &Rules, opponents: &[Shape]) -> Analysis {
+    let mut analysis = Analysis {
+        best_replies: Vec::with_capacity(opponents.len()),
+        best_total: 0,
+        worst_total: 0,
+        expected_total: 0.0,
+    };
+
+    for &opponent in opponents {
+        let points: Vec<i32> = rules
+            .all_shapes()
+            .map(|reply| reply_points(rules, opponent, reply))
+            .collect();
+        let best = best_reply(rules, opponent);
+
+        analysis.best_replies.push(best);
+        analysis.best_total += reply_points(rules, opponent, best);
+        analysis.worst_total += points.iter().min().unwrap();
+        analysis.expected_total += f64::from(points.iter().sum::<i32>()) / points.len() as f64;
+    }
+
+    analysis
+}
+
+impl Rpc {
+    /// Analyses the replies to the opponent column of the guide.
+    pub fn analyse(&self) -> Analysis {
+        let opponents: Vec<Shape> = self.guide.iter().map(|guide_line| guide_line.p1).collect();
+
+        analyse(&self.rules, &opponents)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day2::scoring::{DRAW_POINTS, WIN_POINTS};
+
+    #[test]
+    fn best_reply_wins_with_the_most_valuable_shape() {
+        let rules = Rules::rock_paper_scissors_lizard_spock();
+
+        for opponent in rules.all_shapes() {
+            let best = best_reply(&rules, opponent);
+            assert!(rules.beats(best, opponent), "{}", rules.name(opponent));
+            for reply in rules.all_shapes() {
+                assert!(
+                    reply_points(&rules, opponent, reply) <= reply_points(&rules, opponent, best)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn example_analysis() {
+        let rules = Rules::rock_paper_scissors();
+        let [rock, paper, scissors] = [Shape(0), Shape(1), Shape(2)];
+        let analysis = analyse(&rules, &[rock, paper, scissors]);
+
+        assert_eq!(analysis.best_replies, [paper, scissors, rock]);
+        assert_eq!(analysis.best_total, 8 + 9 + 7);
+        // Scissors against rock, rock against paper, paper against scissors.
+        assert_eq!(analysis.worst_total, 3 + 1 + 2);
+        assert_eq!(analysis.expected_total, 15.0);
+    }
+
+    #[test]
+    fn expected_total_lies_between_the_worst_and_the_best() {
+        for rules in [Rules::rock_paper_scissors(), Rules::cyclic(7).unwrap()] {
+            let opponents: Vec<Shape> = rules.all_shapes().collect();
+            let analysis = analyse(&rules, &opponents);
+
+            assert!(f64::from(analysis.worst_total) <= analysis.expected_total);
+            assert!(analysis.expected_total <= f64::from(analysis.best_total));
+            // In a balanced game a random reply wins, draws and loses equally
+            // often, and is worth the mean shape value.
+            let shape_count = rules.shape_count() as f64;
+            let mean_value = (shape_count + 1.0) / 2.0;
+            let mean_outcome = f64::from(WIN_POINTS) * (shape_count - 1.0) / 2.0 / shape_count
+                + f64::from(DRAW_POINTS) / shape_count;
+            assert!(
+                (analysis.expected_total - shape_count * (mean_value + mean_outcome)).abs() < 1e-9
+            );
+        }
+    }
+}
@@ -0,0 +1,391 @@
+use std::cmp::Ordering;
+
+use super::RpcOutcome;
+use crate::error::{Error, Result};
+
+/// A shape of a [`Rules`] game, by its position in [`Rules::shapes`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Shape(pub usize);
+
+/// A shape's name, the points for playing it and the letters standing for it
+/// in the strategy guide.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ShapeRule {
+    pub name: String,
+    pub value: i32,
+    pub opponent_letter: char,
+    pub player_letter: char,
+}
+
+/// A rock-paper-scissors like game: its shapes, which shape beats which and
+/// the letters the strategy guide uses for them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Rules {
+    pub shapes: Vec<ShapeRule>,
+    /// `beats[a][b]` holds if shape `a` beats shape `b`.
+    beats: Vec<Vec<bool>>,
+    /// Letters standing for lose, draw and win when the second column of the
+    /// guide is read as outcomes.
+    pub outcome_letters: [char; 3],
+}
+
+/// Letters for lose, draw and win unless a rules file says otherwise.
+pub const OUTCOME_LETTERS: [char; 3] = ['X', 'Y', 'Z'];
+
+impl Rules {
+    /// Checks the shapes have distinct names and letters, and that `beats`,
+    /// pairs of (winner, loser) positions in `shapes`, never has a shape
+    /// beat itself or two shapes beat each other.
+    pub fn new(
+        shapes: Vec<ShapeRule>,
+        beats: &[(usize, usize)],
+        outcome_letters: [char; 3],
+    ) -> Result<Rules> {
+        if shapes.is_empty() {
+            return Err(Error::validation("A game needs at least one shape"));
+        }
+        for (index, shape) in shapes.iter().enumerate() {
+            if let Some(other) = shapes[..index].iter().find(|other| {
+                other.name == shape.name
+                    || other.opponent_letter == shape.opponent_letter
+                    || other.player_letter == shape.player_letter
+            }) {
+                return Err(Error::validation(format!(
+                    "Shapes {} and {} share a name or a letter",
+                    other.name, shape.name
+                )));
+            }
+        }
+        if outcome_letters[0] == outcome_letters[1]
+            || outcome_letters[1] == outcome_letters[2]
+            || outcome_letters[0] == outcome_letters[2]
+        {
+            return Err(Error::validation("Outcome letters must be distinct"));
+        }
+
+        let mut beats_matrix = vec![vec![false; shapes.len()]; shapes.len()];
+        for &(winner, loser) in beats {
+            let name = |index: usize| match shapes.get(index) {
+                Some(shape) => Ok(&shape.name),
+                None => Err(Error::validation(format!("There is no shape {index}"))),
+            };
+            let (winner_name, loser_name) = (name(winner)?, name(loser)?);
+            if winner == loser {
+                return Err(Error::validation(format!(
+                    "{winner_name} cannot beat itself"
+                )));
+            }
+            if beats_matrix[loser][winner] {
+                return Err(Error::validation(format!(
+                    "{winner_name} and {loser_name} cannot beat each other"
+                )));
+            }
+            beats_matrix[winner][loser] = true;
+        }
+
+        Ok(Rules {
+            shapes,
+            beats: beats_matrix,
+            outcome_letters,
+        })
+    }
+
+    /// The odd `shape_count` game where every shape beats the half of the
+    /// others before it, wrapping around. Shapes are worth 1 to
+    /// `shape_count`, opponents use letters from `A` and players letters up
+    /// to `Z`.
+    pub fn cyclic(shape_count: usize) -> Result<Rules> {
+        if shape_count.is_multiple_of(2) || !(3..=13).contains(&shape_count) {
+            return Err(Error::validation(
+                "Cyclic games need an odd number of shapes from 3 to 13",
+            ));
+        }
+
+        let shapes = (0..shape_count)
+            .map(|index| ShapeRule {
+                name: format!("Shape {}", index + 1),
+                value: index as i32 + 1,
+                opponent_letter: (b'A' + index as u8) as char,
+                player_letter: (b'Z' + 1 + index as u8 - shape_count as u8) as char,
+            })
+            .collect();
+        let beats: Vec<(usize, usize)> = (0..shape_count)
+            .flat_map(|winner| {
+                (1..=shape_count / 2)
+                    .map(move |step| (winner, (winner + shape_count - step) % shape_count))
+            })
+            .collect();
+
+        Rules::new(shapes, &beats, OUTCOME_LETTERS)
+    }
+
+    pub fn rock_paper_scissors() -> Rules {
+        Rules::cyclic(3)
+            .unwrap()
+            .with_names(&["Rock", "Paper", "Scissors"])
+    }
+
+    pub fn rock_paper_scissors_lizard_spock() -> Rules {
+        let shapes = ["Rock", "Paper", "Scissors", "Lizard", "Spock"]
+            .iter()
+            .enumerate()
+            .map(|(index, name)| ShapeRule {
+                name: name.to_string(),
+                value: index as i32 + 1,
+                opponent_letter: (b'A' + index as u8) as char,
+                player_letter: (b'V' + index as u8) as char,
+            })
+            .collect();
+        let [rock, paper, scissors, lizard, spock] = [0, 1, 2, 3, 4];
+        let beats = [
+            (scissors, paper),
+            (paper, rock),
+            (rock, lizard),
+            (lizard, spock),
+            (spock, scissors),
+            (scissors, lizard),
+            (lizard, paper),
+            (paper, spock),
+            (spock, rock),
+            (rock, scissors),
+        ];
+
+        Rules::new(shapes, &beats, OUTCOME_LETTERS).unwrap()
+    }
+
+    fn with_names(mut self, names: &[&str]) -> Rules {
+        for (shape, name) in self.shapes.iter_mut().zip(names) {
+            shape.name = name.to_string();
+        }
+        self
+    }
+
+    pub fn shape_count(&self) -> usize {
+        self.shapes.len()
+    }
+
+    /// Every shape, in the order of [`Rules::shapes`].
+    pub fn all_shapes(&self) -> impl Iterator<Item = Shape> {
+        (0..self.shapes.len()).map(Shape)
+    }
+
+    pub fn name(&self, shape: Shape) -> &str {
+        &self.shapes[shape.0].name
+    }
+
+    pub fn value(&self, shape: Shape) -> i32 {
+        self.shapes[shape.0].value
+    }
+
+    pub fn beats(&self, shape: Shape, other: Shape) -> bool {
+        self.beats[shape.0][other.0]
+    }
+
+    /// How `shape` fares against `other`: `Greater` if it beats it, `Less` if
+    /// it is beaten and `Equal` if neither beats the other.
+    pub fn compare(&self, shape: Shape, other: Shape) -> Ordering {
+        match (self.beats(shape, other), self.beats(other, shape)) {
+            (true, _) => Ordering::Greater,
+            (_, true) => Ordering::Less,
+            _ => Ordering::Equal,
+        }
+    }
+
+    /// The first shape that reaches `outcome` when played against `opponent`.
+    pub fn shape_for_outcome(&self, opponent: Shape, outcome: RpcOutcome) -> Option<Shape> {
+        self.all_shapes()
+            .find(|&shape| self.compare(shape, opponent) == outcome.ordering())
+    }
+
+    pub fn opponent_shape(&self, letter: char) -> Option<Shape> {
+        self.all_shapes()
+            .find(|shape| self.shapes[shape.0].opponent_letter == letter)
+    }
+
+    pub fn player_shape(&self, letter: char) -> Option<Shape> {
+        self.all_shapes()
+            .find(|shape| self.shapes[shape.0].player_letter == letter)
+    }
+
+    pub fn outcome(&self, letter: char) -> Option<RpcOutcome> {
+        let position = self
+            .outcome_letters
+            .iter()
+            .position(|&outcome_letter| outcome_letter == letter)?;
+        Some([RpcOutcome::Lose, RpcOutcome::Draw, RpcOutcome::Win][position])
+    }
+}
+
+impl Default for Rules {
+    fn default() -> Rules {
+        Rules::rock_paper_scissors()
+    }
+}
+
+/// Reads a rules file. Each line is one of
+///
+/// - `shape <name> <value> <opponent letter> <player letter>`
+/// - `beats <winner> <loser>`
+/// - `outcomes <lose letter> <draw letter> <win letter>`, by default `X Y Z`
+///
+/// Blank lines and lines starting with `#` are ignored.
+pub fn read_rules(input: &str) -> Result<Rules> {
+    let mut shapes: Vec<ShapeRule> = Vec::new();
+    let mut beats: Vec<(usize, usize)> = Vec::new();
+    let mut outcome_letters = OUTCOME_LETTERS;
+
+    for (line_index, line) in input.lines().enumerate() {
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+
+        let error =
+            |token: &str, message: String| Error::parse_token(line_index + 1, line, token, message);
+        let read_letter = |token: &str| {
+            let mut chars = token.chars();
+            match (chars.next(), chars.next()) {
+                (Some(letter), None) => Ok(letter),
+                _ => Err(error(token, "Expected a single letter".to_string())),
+            }
+        };
+        let find_shape = |token: &str| {
+            shapes
+                .iter()
+                .position(|shape| shape.name == token)
+                .ok_or_else(|| error(token, format!("There is no shape named {token}")))
+        };
+
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        match tokens.as_slice() {
+            ["shape", name, value, opponent_letter, player_letter] => {
+                if shapes.iter().any(|shape| shape.name == *name) {
+                    return Err(error(name, format!("Shape {name} is already defined")));
+                }
+                let value = value
+                    .parse::<i32>()
+                    .map_err(|_| error(value, "Shape value is not a number".to_string()))?;
+                shapes.push(ShapeRule {
+                    name: name.to_string(),
+                    value,
+                    opponent_letter: read_letter(opponent_letter)?,
+                    player_letter: read_letter(player_letter)?,
+                });
+            }
+            ["beats", winner, loser] => {
+                let (winner_index, loser_index) = (find_shape(winner)?, find_shape(loser)?);
+                if winner_index == loser_index {
+                    return Err(error(loser, format!("{winner} cannot beat itself")));
+                }
+                if beats.contains(&(loser_index, winner_index)) {
+                    return Err(error(winner, format!("{loser} already beats {winner}")));
+                }
+                beats.push((winner_index, loser_index));
+            }
+            ["outcomes", lose, draw, win] => {
+                outcome_letters = [read_letter(lose)?, read_letter(draw)?, read_letter(win)?];
+            }
+            _ => {
+                return Err(Error::parse(
+                    line_index + 1,
+                    line,
+                    "Expected \"shape <name> <value> <letter> <letter>\", \
+                     \"beats <shape> <shape>\" or \"outcomes <letter> <letter> <letter>\"",
+                ))
+            }
+        }
+    }
+
+    Rules::new(shapes, &beats, outcome_letters)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RPSLS: &str = "\
+# Rock paper scissors lizard Spock
+shape Rock 1 A V
+shape Paper 2 B W
+shape Scissors 3 C X
+shape Lizard 4 D Y
+shape Spock 5 E Z
+beats Scissors Paper
+beats Paper Rock
+beats Rock Lizard
+beats Lizard Spock
+beats Spock Scissors
+beats Scissors Lizard
+beats Lizard Paper
+beats Paper Spock
+beats Spock Rock
+beats Rock Scissors
+outcomes L D W
+";
+
+    /// Every shape of a balanced game beats exactly half of the others.
+    fn assert_balanced(rules: &Rules) {
+        for shape in rules.all_shapes() {
+            let wins = rules
+                .all_shapes()
+                .filter(|&other| rules.beats(shape, other))
+                .count();
+            assert_eq!(wins, rules.shape_count() / 2, "{}", rules.name(shape));
+            assert_eq!(rules.compare(shape, shape), Ordering::Equal);
+        }
+    }
+
+    #[test]
+    fn rock_paper_scissors() {
+        let rules = Rules::rock_paper_scissors();
+        let [rock, paper, scissors] = [Shape(0), Shape(1), Shape(2)];
+
+        assert_eq!(rules.compare(paper, rock), Ordering::Greater);
+        assert_eq!(rules.compare(rock, paper), Ordering::Less);
+        assert_eq!(rules.compare(rock, scissors), Ordering::Greater);
+        assert_eq!(rules.compare(scissors, paper), Ordering::Greater);
+        assert_eq!(rules.opponent_shape('B'), Some(paper));
+        assert_eq!(rules.player_shape('Z'), Some(scissors));
+        assert_eq!(rules.outcome('Y'), Some(RpcOutcome::Draw));
+    }
+
+    #[test]
+    fn cyclic_games_are_balanced() {
+        for shape_count in [3, 5, 7, 13] {
+            assert_balanced(&Rules::cyclic(shape_count).unwrap());
+        }
+        assert!(Rules::cyclic(4).is_err());
+        assert!(Rules::cyclic(15).is_err());
+    }
+
+    #[test]
+    fn reads_rules_files() {
+        let rules = read_rules(RPSLS).unwrap();
+        assert_balanced(&rules);
+
+        let spock = rules.player_shape('Z').unwrap();
+        let lizard = rules.opponent_shape('D').unwrap();
+        assert_eq!(rules.name(spock), "Spock");
+        assert_eq!(rules.compare(lizard, spock), Ordering::Greater);
+        assert_eq!(rules.outcome('W'), Some(RpcOutcome::Win));
+        assert_eq!(rules.outcome('Z'), None);
+
+        let rules = Rules {
+            outcome_letters: OUTCOME_LETTERS,
+            ..rules
+        };
+        assert_eq!(rules, Rules::rock_paper_scissors_lizard_spock());
+    }
+
+    #[test]
+    fn rejects_inconsistent_rules() {
+        let shapes = "shape Rock 1 A X\nshape Paper 2 B Y\n";
+
+        assert!(read_rules(&format!("{shapes}beats Rock Rock\n")).is_err());
+        assert!(read_rules(&format!("{shapes}beats Rock Paper\nbeats Paper Rock\n")).is_err());
+        assert!(read_rules(&format!("{shapes}beats Rock Spock\n")).is_err());
+        assert!(read_rules(&format!("{shapes}shape Rock 3 C Z\n")).is_err());
+        assert!(read_rules(&format!("{shapes}shape Spock 3 A Z\n")).is_err());
+        assert!(read_rules("shape Rock one A X\n").is_err());
+        assert!(read_rules("").is_err());
+    }
+}
@@ -0,0 +1,186 @@
+use std::cmp::Ordering;
+
+use super::{rules::Rules, RpcRound};
+
+pub const LOSS_POINTS: i32 = 0;
+pub const DRAW_POINTS: i32 = 3;
+pub const WIN_POINTS: i32 = 6;
+
+/// Points for the outcome of a round, from the side of the player whose
+/// shape compares as `ordering` against the other's.
+pub fn outcome_points(ordering: Ordering) -> i32 {
+    match ordering {
+        Ordering::Less => LOSS_POINTS,
+        Ordering::Equal => DRAW_POINTS,
+        Ordering::Greater => WIN_POINTS,
+    }
+}
+
+/// Points both players score in one round, split into the points for the
+/// shape played and for the outcome.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RoundScore {
+    pub p1_shape: i32,
+    pub p1_outcome: i32,
+    pub p2_shape: i32,
+    pub p2_outcome: i32,
+}
+
+impl RoundScore {
+    pub fn p1(&self) -> i32 {
+        self.p1_shape + self.p1_outcome
+    }
+
+    pub fn p2(&self) -> i32 {
+        self.p2_shape + self.p2_outcome
+    }
+}
+
+pub fn score_round(rules: &Rules, round: &RpcRound) -> RoundScore {
+    RoundScore {
+        p1_shape: rules.value(round.p1),
+        p1_outcome: outcome_points(rules.compare(round.p1, round.p2)),
+        p2_shape: rules.value(round.p2),
+        p2_outcome: outcome_points(rules.compare(round.p2, round.p1)),
+    }
+}
+
+/// Every round's score and both players' totals.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Scores {
+    pub rounds: Vec<RoundScore>,
+    pub p1_total: i32,
+    pub p2_total: i32,
+}
+
+pub fn score_rounds(rules: &Rules, rounds: &[RpcRound]) -> Scores {
+    let rounds: Vec<RoundScore> = rounds
+        .iter()
+        .map(|round| score_round(rules, round))
+        .collect();
+
+    Scores {
+        p1_total: rounds.iter().map(RoundScore::p1).sum(),
+        p2_total: rounds.iter().map(RoundScore::p2).sum(),
+        rounds,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{day2::rules::Shape, test_util::Xorshift};
+
+    fn games() -> [Rules; 3] {
+        [
+            Rules::rock_paper_scissors(),
+            Rules::rock_paper_scissors_lizard_spock(),
+            Rules::cyclic(7).unwrap(),
+        ]
+    }
+
+    /// Every pair of shapes of `rules`.
+    fn all_rounds(rules: &Rules) -> impl Iterator<Item = RpcRound> + '_ {
+        rules
+            .all_shapes()
+            .flat_map(|p1| rules.all_shapes().map(move |p2| RpcRound { p1, p2 }))
+    }
+
+    fn random_rounds(rules: &Rules, seed: u64, count: usize) -> Vec<RpcRound> {
+        let mut random = Xorshift::new(seed);
+        let mut next = move || Shape(random.below(rules.shape_count()));
+
+        (0..count)
+            .map(|_| RpcRound {
+                p1: next(),
+                p2: next(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn outcome_points_of_a_round_sum_to_six() {
+        for rules in games() {
+            for round in all_rounds(&rules) {
+                let score = score_round(&rules, &round);
+                assert_eq!(score.p1_outcome + score.p2_outcome, 6, "{round:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn swapping_players_swaps_scores() {
+        for rules in games() {
+            for round in all_rounds(&rules) {
+                let score = score_round(&rules, &round);
+                let swapped = score_round(
+                    &rules,
+                    &RpcRound {
+                        p1: round.p2,
+                        p2: round.p1,
+                    },
+                );
+                assert_eq!((score.p1(), score.p2()), (swapped.p2(), swapped.p1()));
+            }
+        }
+    }
+
+    #[test]
+    fn shape_points_are_the_shape_value() {
+        for rules in games() {
+            for round in all_rounds(&rules) {
+                let score = score_round(&rules, &round);
+                assert_eq!(score.p1_shape, rules.value(round.p1));
+                assert_eq!(score.p2_shape, rules.value(round.p2));
+            }
+        }
+    }
+
+    #[test]
+    fn totals_are_the_sum_of_the_breakdown() {
+        for rules in games() {
+            for seed in 1..=20 {
+                let rounds = random_rounds(&rules, seed, 50);
+                let scores = score_rounds(&rules, &rounds);
+
+                assert_eq!(scores.rounds.len(), rounds.len());
+                assert_eq!(
+                    scores.p1_total,
+                    scores.rounds.iter().map(RoundScore::p1).sum::<i32>()
+                );
+                assert_eq!(
+                    scores.p2_total,
+                    scores.rounds.iter().map(RoundScore::p2).sum::<i32>()
+                );
+                let outcome_total: i32 = scores
+                    .rounds
+                    .iter()
+                    .map(|score| score.p1_outcome + score.p2_outcome)
+                    .sum();
+                assert_eq!(outcome_total, 6 * rounds.len() as i32);
+            }
+        }
+    }
+
+    #[test]
+    fn winner_takes_six_loser_nothing() {
+        let rules = Rules::rock_paper_scissors();
+        let score = score_round(
+            &rules,
+            &RpcRound {
+                p1: Shape(0),
+                p2: Shape(1),
+            },
+        );
+
+        assert_eq!(
+            score,
+            RoundScore {
+                p1_shape: 1,
+                p1_outcome: LOSS_POINTS,
+                p2_shape: 2,
+                p2_outcome: WIN_POINTS,
+            }
+        );
+    }
+}
@@ -0,0 +1,376 @@
+use std::cmp::{Ordering, Reverse};
+
+use super::{
+    rules::{Rules, Shape},
+    scoring::score_rounds,
+    Rpc, RpcRound, Strategy,
+};
+use crate::error::{Error, Result};
+
+/// League points for winning, drawing and losing a match.
+pub const MATCH_WIN_POINTS: u32 = 3;
+pub const MATCH_DRAW_POINTS: u32 = 1;
+pub const MATCH_LOSS_POINTS: u32 = 0;
+
+/// A guide taking part in a tournament, by the shapes it plays each round.
+#[derive(Debug)]
+pub struct Entrant {
+    pub name: String,
+    pub shapes: Vec<Shape>,
+}
+
+impl Entrant {
+    /// Plays the shapes the guide picks with `strategy`.
+    pub fn from_guide(name: impl Into<String>, rpc: &Rpc, strategy: Strategy) -> Result<Entrant> {
+        Ok(Entrant {
+            name: name.into(),
+            shapes: rpc.rounds(strategy)?.iter().map(|round| round.p2).collect(),
+        })
+    }
+}
+
+/// Totals of a match, from the side of the entrant listed first.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Match {
+    pub rounds: usize,
+    pub p1_total: i32,
+    pub p2_total: i32,
+}
+
+impl Match {
+    pub fn ordering(&self) -> Ordering {
+        self.p1_total.cmp(&self.p2_total)
+    }
+}
+
+/// Plays two guides against each other, which must have as many rounds.
+pub fn play_match(rules: &Rules, p1: &[Shape], p2: &[Shape]) -> Result<Match> {
+    if p1.len() != p2.len() {
+        return Err(Error::validation(format!(
+            "Guides of {} and {} rounds cannot play a match",
+            p1.len(),
+            p2.len()
+        )));
+    }
+
+    let rounds: Vec<RpcRound> = p1
+        .iter()
+        .zip(p2)
+        .map(|(&p1, &p2)| RpcRound { p1, p2 })
+        .collect();
+    let scores = score_rounds(rules, &rounds);
+
+    Ok(Match {
+        rounds: rounds.len(),
+        p1_total: scores.p1_total,
+        p2_total: scores.p2_total,
+    })
+}
+
+/// An entrant's line in the league table.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Standing {
+    pub entrant: usize,
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+    pub points: u32,
+    pub score_for: i32,
+    pub score_against: i32,
+}
+
+impl Standing {
+    pub fn played(&self) -> u32 {
+        self.wins + self.draws + self.losses
+    }
+}
+
+#[derive(Debug)]
+pub struct Tournament {
+    pub names: Vec<String>,
+    /// `matches[a][b]` is the match of entrant `a` against `b`, from `a`'s
+    /// side, and `None` on the diagonal.
+    pub matches: Vec<Vec<Option<Match>>>,
+    /// Standings by points, then score difference, then score for, then the
+    /// order of the entrants.
+    pub table: Vec<Standing>,
+}
+
+/// Plays every entrant once against every other.
+pub fn run_tournament(rules: &Rules, entrants: &[Entrant]) -> Result<Tournament> {
+    if entrants.len() < 2 {
+        return Err(Error::validation("A tournament needs at least two guides"));
+    }
+    for (index, entrant) in entrants.iter().enumerate() {
+        if entrants[..index]
+            .iter()
+            .any(|other| other.name == entrant.name)
+        {
+            return Err(Error::validation(format!(
+                "Two guides are named {}",
+                entrant.name
+            )));
+        }
+    }
+    let first = &entrants[0];
+    if let Some(other) = entrants
+        .iter()
+        .find(|entrant| entrant.shapes.len() != first.shapes.len())
+    {
+        return Err(Error::validation(format!(
+            "Guide {} has {} rounds but {} has {}",
+            other.name,
+            other.shapes.len(),
+            first.name,
+            first.shapes.len()
+        )));
+    }
+
+    let mut matches = vec![vec![None; entrants.len()]; entrants.len()];
+    let mut table: Vec<Standing> = (0..entrants.len())
+        .map(|entrant| Standing {
+            entrant,
+            ..Standing::default()
+        })
+        .collect();
+
+    for a in 0..entrants.len() {
+        for b in a + 1..entrants.len() {
+            let played = play_match(rules, &entrants[a].shapes, &entrants[b].shapes)?;
+            matches[a][b] = Some(played);
+            matches[b][a] = Some(Match {
+                rounds: played.rounds,
+                p1_total: played.p2_total,
+                p2_total: played.p1_total,
+            });
+        }
+    }
+
+    for (standing, row) in table.iter_mut().zip(&matches) {
+        for played in row.iter().flatten() {
+            match played.ordering() {
+                Ordering::Greater => {
+                    standing.wins += 1;
+                    standing.points += MATCH_WIN_POINTS;
+                }
+                Ordering::Equal => {
+                    standing.draws += 1;
+                    standing.points += MATCH_DRAW_POINTS;
+                }
+                Ordering::Less => {
+                    standing.losses += 1;
+                    standing.points += MATCH_LOSS_POINTS;
+                }
+            }
+            standing.score_for += played.p1_total;
+            standing.score_against += played.p2_total;
+        }
+    }
+    table.sort_by_key(|standing| {
+        (
+            Reverse(standing.points),
+            Reverse(standing.score_for - standing.score_against),
+            Reverse(standing.score_for),
+            standing.entrant,
+        )
+    });
+
+    Ok(Tournament {
+        names: entrants
+            .iter()
+            .map(|entrant| entrant.name.clone())
+            .collect(),
+        matches,
+        table,
+    })
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn csv_line(fields: &[String]) -> String {
+    fields
+        .iter()
+        .map(|field| csv_field(field))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+impl Tournament {
+    /// Rows of the league table, with the position first.
+    pub fn table_rows(&self) -> Vec<Vec<String>> {
+        self.table
+            .iter()
+            .enumerate()
+            .map(|(position, standing)| {
+                vec![
+                    (position + 1).to_string(),
+                    self.names[standing.entrant].clone(),
+                    standing.played().to_string(),
+                    standing.wins.to_string(),
+                    standing.draws.to_string(),
+                    standing.losses.to_string(),
+                    standing.score_for.to_string(),
+                    standing.score_against.to_string(),
+                    standing.points.to_string(),
+                ]
+            })
+            .collect()
+    }
+
+    /// Rows of the head-to-head matrix, the row entrant's total first.
+    pub fn matrix_rows(&self) -> Vec<Vec<String>> {
+        self.matches
+            .iter()
+            .enumerate()
+            .map(|(entrant, row)| {
+                let mut cells = vec![self.names[entrant].clone()];
+                cells.extend(row.iter().map(|played| match played {
+                    Some(played) => format!("{}-{}", played.p1_total, played.p2_total),
+                    None => "-".to_string(),
+                }));
+                cells
+            })
+            .collect()
+    }
+
+    /// The league table then the head-to-head matrix as CSV, separated by a
+    /// blank line.
+    pub fn to_csv(&self) -> String {
+        let mut lines = vec![csv_line(&TABLE_HEADER.map(String::from))];
+        lines.extend(self.table_rows().iter().map(|row| csv_line(row)));
+        lines.push(String::new());
+
+        let mut header = vec![String::new()];
+        header.extend(self.names.iter().cloned());
+        lines.push(csv_line(&header));
+        lines.extend(self.matrix_rows().iter().map(|row| csv_line(row)));
+
+        lines.join("\n")
+    }
+}
+
+/// Columns of [`Tournament::table_rows`].
+pub const TABLE_HEADER: [&str; 9] = [
+    "Pos", "Guide", "Played", "Won", "Drawn", "Lost", "For", "Against", "Points",
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entrant(name: &str, shapes: &[usize]) -> Entrant {
+        Entrant {
+            name: name.to_string(),
+            shapes: shapes.iter().map(|&shape| Shape(shape)).collect(),
+        }
+    }
+
+    #[test]
+    fn matches_score_every_round() {
+        let rules = Rules::rock_paper_scissors();
+        let played = play_match(&rules, &[Shape(0), Shape(0)], &[Shape(1), Shape(0)]);
+
+        assert_eq!(
+            played.unwrap(),
+            Match {
+                rounds: 2,
+                p1_total: 1 + 1 + 3,
+                p2_total: 2 + 6 + 1 + 3,
+            }
+        );
+    }
+
+    #[test]
+    fn guides_of_different_lengths_are_rejected() {
+        let rules = Rules::rock_paper_scissors();
+        assert!(play_match(
+            &rules,
+            &[Shape(0), Shape(0), Shape(2)],
+            &[Shape(1), Shape(0)]
+        )
+        .is_err());
+
+        let entrants = [entrant("long", &[0, 0, 2]), entrant("short", &[1, 0])];
+        match run_tournament(&rules, &entrants) {
+            Err(Error::Validation(message)) => {
+                assert_eq!(message, "Guide short has 2 rounds but long has 3")
+            }
+            result => panic!("expected a validation error, got {result:?}"),
+        }
+    }
+
+    #[test]
+    fn guides_must_have_different_names() {
+        let rules = Rules::rock_paper_scissors();
+        let entrants = [entrant("g", &[0]), entrant("h", &[1]), entrant("g", &[2])];
+
+        match run_tournament(&rules, &entrants) {
+            Err(Error::Validation(message)) => assert_eq!(message, "Two guides are named g"),
+            result => panic!("expected a validation error, got {result:?}"),
+        }
+    }
+
+    #[test]
+    fn league_table_and_head_to_head() {
+        let rules = Rules::rock_paper_scissors();
+        let entrants = [
+            entrant("rocks", &[0, 0]),
+            entrant("papers", &[1, 1]),
+            entrant("scissors", &[2, 2]),
+            entrant("copy", &[2, 2]),
+        ];
+        let tournament = run_tournament(&rules, &entrants).unwrap();
+
+        let order: Vec<&str> = tournament
+            .table
+            .iter()
+            .map(|standing| tournament.names[standing.entrant].as_str())
+            .collect();
+        assert_eq!(order, ["rocks", "scissors", "copy", "papers"]);
+        let rocks = &tournament.table[0];
+        assert_eq!(
+            (rocks.wins, rocks.draws, rocks.losses, rocks.points),
+            (2, 0, 1, 6)
+        );
+        assert_eq!(tournament.table[1].draws, 1);
+
+        for (a, row) in tournament.matches.iter().enumerate() {
+            assert_eq!(row[a], None);
+            for (b, played) in row.iter().enumerate().filter(|&(b, _)| b != a) {
+                let reverse = tournament.matches[b][a].unwrap();
+                assert_eq!(played.unwrap().p1_total, reverse.p2_total);
+            }
+        }
+        assert_eq!(tournament.matrix_rows()[0][2], "2-16");
+    }
+
+    #[test]
+    fn csv_has_the_table_then_the_matrix() {
+        let rules = Rules::rock_paper_scissors();
+        let entrants = [entrant("rocks", &[0]), entrant("a, \"b\"", &[1])];
+        let csv = run_tournament(&rules, &entrants).unwrap().to_csv();
+
+        assert_eq!(
+            csv,
+            "Pos,Guide,Played,Won,Drawn,Lost,For,Against,Points\n\
+             1,\"a, \"\"b\"\"\",1,1,0,0,8,1,3\n\
+             2,rocks,1,0,0,1,1,8,0\n\
+             \n\
+             ,rocks,\"a, \"\"b\"\"\"\n\
+             rocks,-,1-8\n\
+             \"a, \"\"b\"\"\",8-1,-"
+        );
+    }
+
+    #[test]
+    fn needs_two_guides() {
+        let rules = Rules::rock_paper_scissors();
+        assert!(run_tournament(&rules, &[entrant("alone", &[0])]).is_err());
+    }
+}
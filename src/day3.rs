@@ -1,125 +1,377 @@
-use std::{collections::HashSet, fs::read_to_string, ops::ControlFlow, path::Path};
+use crate::{
+    diagnostics::Diagnostics,
+    error::{Error, Result},
+    solution::Solution,
+};
 
-trait Priority {
-    fn to_priority(&self) -> Option<u32>;
-}
+use self::{
+    groups::{badge_priorities, GROUP_SIZE},
+    item_set::ItemSet,
+    priorities::PriorityTable,
+};
 
-impl Priority for char {
+pub mod groups;
+pub mod item_set;
+pub mod priorities;
+pub mod repack;
+
+pub trait Priority {
+    fn priority_in(&self, table: &PriorityTable) -> Option<u32>;
+
+    /// Priority in the puzzle's own scheme.
     fn to_priority(&self) -> Option<u32> {
-        match self {
-            'A'..='Z' => {
-                let digit = self.clone() as u32;
-                Some(digit - 65 + 27)
-            }
+        self.priority_in(&PriorityTable::Aoc)
+    }
+}
 
-            'a'..='z' => {
-                let digit = self.clone() as u32;
-                Some(digit - 97 + 1)
-            }
-            _ => None,
-        }
+impl Priority for char {
+    fn priority_in(&self, table: &PriorityTable) -> Option<u32> {
+        table.priority(*self)
     }
 }
 
-struct Compartment {
-    contents: Vec<char>,
+pub struct Compartment {
+    pub contents: Vec<char>,
 }
 
-struct Rucksack {
-    compartments: Vec<Compartment>,
+pub struct Rucksack {
+    pub compartments: Vec<Compartment>,
 }
 
 impl Rucksack {
-    fn get_packing_errors_priority(&self) -> u32 {
-        let mut errors: HashSet<char> = HashSet::new();
+    /// Every item of the rucksack, compartment after compartment.
+    pub fn items(&self) -> impl Iterator<Item = char> + '_ {
         self.compartments
-            .get(0)
-            .unwrap()
-            .contents
             .iter()
-            .for_each(|char| {
-                if self.compartments.get(1).unwrap().contents.contains(char) {
-                    errors.insert(*char);
-                }
-            });
+            .flat_map(|compartment| compartment.contents.iter().copied())
+    }
 
-        errors.iter().fold(0, |priorities, char| {
-            priorities + char.to_priority().unwrap_or_default()
-        })
+    /// Priorities of the items found in every compartment, in the puzzle's
+    /// scheme.
+    pub fn get_packing_errors_priority(&self) -> u64 {
+        self.get_packing_errors_priority_anysize(&PriorityTable::default(), SharedBy::All)
     }
 
-    fn get_packing_errors_priority_anysize(&self) -> u32 {
-        let mut errors: HashSet<char> = HashSet::new();
-        let mut compartment_sets: Vec<HashSet<char>> = Vec::new();
+    /// Items packed in more than one compartment. With
+    /// [`SharedBy::AtLeastTwo`] an item counts if any two compartments hold
+    /// it, with [`SharedBy::All`] only if every compartment does. The two
+    /// agree for two compartments.
+    pub fn packing_error_set(&self, table: &PriorityTable, shared_by: SharedBy) -> ItemSet {
+        let compartment_sets = self
+            .compartments
+            .iter()
+            .map(|compartment| ItemSet::from_items(table, compartment.contents.iter().copied()));
 
-        self.compartments.iter().skip(1).for_each(|compartment| {
-            let compartment_set = HashSet::from_iter(compartment.contents.iter().cloned());
-            compartment_sets.push(compartment_set);
-        });
+        match shared_by {
+            SharedBy::AtLeastTwo => {
+                let mut seen = ItemSet::new();
+                let mut shared = ItemSet::new();
+                for compartment_set in compartment_sets {
+                    shared = shared | (seen & compartment_set);
+                    seen = seen | compartment_set;
+                }
+                shared
+            }
+            SharedBy::All if self.compartments.len() < 2 => ItemSet::new(),
+            SharedBy::All => compartment_sets.reduce(ItemSet::intersection).unwrap(),
+        }
+    }
 
-        self.compartments
-            .iter()
-            .enumerate()
-            .for_each(|(index, compartment)| {
-                compartment.contents.iter().for_each(|char| {
-                    compartment_sets
-                        .iter()
-                        .skip(index)
-                        .try_for_each(|compartment_set| {
-                            if compartment_set.contains(char) {
-                                errors.insert(*char);
-                                return ControlFlow::Break(());
-                            }
-                            ControlFlow::Continue(())
-                        });
-                });
-            });
-
-        errors.iter().fold(0, |priorities, char| {
-            priorities + char.to_priority().unwrap_or_default()
-        })
+    /// The items of [`Rucksack::packing_error_set`], lowest priority first.
+    pub fn packing_errors(&self, table: &PriorityTable, shared_by: SharedBy) -> Vec<char> {
+        self.items_in(table, self.packing_error_set(table, shared_by))
     }
+
+    /// The distinct items of the rucksack that are in `set`, lowest priority
+    /// first.
+    pub fn items_in(&self, table: &PriorityTable, set: ItemSet) -> Vec<char> {
+        let mut items: Vec<char> = self
+            .items()
+            .filter(|&item| set.contains(table, item))
+            .collect();
+        items.sort_by_key(|&item| (table.priority(item), table.position(item)));
+        items.dedup();
+        items
+    }
+
+    pub fn get_packing_errors_priority_anysize(
+        &self,
+        table: &PriorityTable,
+        shared_by: SharedBy,
+    ) -> u64 {
+        self.packing_error_set(table, shared_by).priority_sum(table)
+    }
+}
+
+fn add_priorities(total: u64, priorities: u64) -> Result<u64> {
+    total
+        .checked_add(priorities)
+        .ok_or_else(|| Error::validation("Priority total does not fit in 64 bits"))
+}
+
+/// Sum of the priorities of every rucksack's packing errors.
+pub fn packing_errors_priority(
+    table: &PriorityTable,
+    rucksacks: &[Rucksack],
+    shared_by: SharedBy,
+) -> Result<u64> {
+    rucksacks.iter().try_fold(0, |total, rucksack| {
+        add_priorities(
+            total,
+            rucksack.get_packing_errors_priority_anysize(table, shared_by),
+        )
+    })
+}
+
+/// Which items of a rucksack with any number of compartments are packing
+/// errors.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum SharedBy {
+    /// Items found in two or more compartments.
+    #[default]
+    AtLeastTwo,
+    /// Items found in every compartment.
+    All,
 }
 
-fn read_rucksacks_contents(
-    path: &Path,
+pub fn read_rucksacks_contents(
+    input: &str,
     rucksack_container_count: usize,
-) -> Result<Vec<Rucksack>, String> {
+    table: &PriorityTable,
+    diagnostics: &mut Diagnostics,
+) -> Result<Vec<Rucksack>> {
     let mut rucksacks: Vec<Rucksack> = Vec::new();
 
-    for line in read_to_string(path).unwrap().lines() {
-        if line.len() % rucksack_container_count != 0 {
-            return Err(format!(
-                "A rucksack is not divisible into {rucksack_container_count} equal containers"
-            ));
-        }
-        let compartment_size = line.len() / rucksack_container_count;
-        let chars: Vec<char> = line.chars().collect();
-
-        let mut rucksack = Rucksack {
-            compartments: Vec::new(),
-        };
+    if rucksack_container_count == 0 {
+        return Err(Error::validation("A rucksack needs at least one container"));
+    }
 
-        for i in 0..2 {
-            rucksack.compartments.push(Compartment {
-                contents: (&chars[(i * compartment_size)..((i + 1) * compartment_size)]).to_vec(),
-            });
+    for (line_index, line) in input.lines().enumerate() {
+        let chars: Vec<char> = line.chars().collect();
+        if chars.is_empty() {
+            diagnostics.reject(Error::parse(line_index + 1, line, "A rucksack is empty"))?;
+            continue;
+        }
+        if !chars.len().is_multiple_of(rucksack_container_count) {
+            diagnostics.reject(Error::parse(
+                line_index + 1,
+                line,
+                format!(
+                    "A rucksack is not divisible into {rucksack_container_count} equal containers"
+                ),
+            ))?;
+            continue;
         }
+        if let Some(column_index) = chars
+            .iter()
+            .position(|char| char.priority_in(table).is_none())
+        {
+            diagnostics.reject(
+                Error::parse(line_index + 1, line, "An item has no priority")
+                    .at(column_index + 1, 1),
+            )?;
+            continue;
+        }
+        let compartment_size = chars.len() / rucksack_container_count;
 
-        rucksacks.push(rucksack);
+        rucksacks.push(Rucksack {
+            compartments: chars
+                .chunks(compartment_size)
+                .map(|contents| Compartment {
+                    contents: contents.to_vec(),
+                })
+                .collect(),
+        });
     }
 
     Ok(rucksacks)
 }
 
-pub fn day3() {
-    let rucksacks = read_rucksacks_contents(Path::new("rucksacks-contents.txt"), 2).unwrap();
-    let priorities: Vec<u32> = rucksacks
-        .iter()
-        .map(|rucksack| rucksack.get_packing_errors_priority_anysize())
-        .collect();
+pub struct Day3;
+
+impl Solution for Day3 {
+    type Puzzle = Vec<Rucksack>;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(input: &str, diagnostics: &mut Diagnostics) -> Result<Vec<Rucksack>> {
+        read_rucksacks_contents(input, 2, &PriorityTable::default(), diagnostics)
+    }
+
+    fn part1(rucksacks: &Vec<Rucksack>) -> Result<u64> {
+        packing_errors_priority(&PriorityTable::default(), rucksacks, SharedBy::default())
+    }
+
+    fn part2(rucksacks: &Vec<Rucksack>) -> Result<u64> {
+        badge_priorities(&PriorityTable::default(), rucksacks, GROUP_SIZE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::{solve_example, Part};
 
-    let priorities_sum: u32 = priorities.iter().sum();
+    pub(super) const EXAMPLE: &str = "\
+vJrwpWtwJgWrhcsFMMfFFhFp
+jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
+PmmdzqPrVvPwwTWBwg
+wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
+ttgJtRGJQctTZtZT
+CrZsJsPPZsGzwwsLwLmpwMDw
+";
+
+    #[test]
+    fn priorities_run_from_lowercase_to_uppercase() {
+        assert_eq!('a'.to_priority(), Some(1));
+        assert_eq!('z'.to_priority(), Some(26));
+        assert_eq!('A'.to_priority(), Some(27));
+        assert_eq!('Z'.to_priority(), Some(52));
+        assert_eq!('1'.to_priority(), None);
+    }
+
+    #[test]
+    fn splits_each_rucksack_into_equal_compartments() {
+        let rucksacks = read_rucksacks_contents(
+            EXAMPLE,
+            2,
+            &PriorityTable::default(),
+            &mut Diagnostics::default(),
+        )
+        .unwrap();
+
+        let first: Vec<String> = rucksacks[0]
+            .compartments
+            .iter()
+            .map(|compartment| compartment.contents.iter().collect())
+            .collect();
+        assert_eq!(first, ["vJrwpWtwJgWr", "hcsFMMfFFhFp"]);
+    }
 
-    print!("{:?}", priorities_sum);
+    #[test]
+    fn priority_totals_do_not_overflow_32_bits() {
+        let table =
+            PriorityTable::custom("abcd".chars().map(|item| (item, u32::MAX)).collect()).unwrap();
+        let rucksacks =
+            read_rucksacks_contents("abab\nac\nad\n", 2, &table, &mut Diagnostics::default())
+                .unwrap();
+
+        assert_eq!(
+            packing_errors_priority(&table, &rucksacks[..1], SharedBy::All).unwrap(),
+            2 * u64::from(u32::MAX)
+        );
+        assert_eq!(
+            badge_priorities(&table, &rucksacks[1..], 2).unwrap(),
+            u64::from(u32::MAX)
+        );
+        assert!(add_priorities(u64::MAX, 1).is_err());
+    }
+
+    #[test]
+    fn honours_the_container_count() {
+        for count in 2..=6 {
+            let line: String = ('a'..='z').take(count * 3).collect();
+            let rucksacks = read_rucksacks_contents(
+                &line,
+                count,
+                &PriorityTable::default(),
+                &mut Diagnostics::default(),
+            )
+            .unwrap();
+
+            let compartments: Vec<String> = rucksacks[0]
+                .compartments
+                .iter()
+                .map(|compartment| compartment.contents.iter().collect())
+                .collect();
+            let expected: Vec<String> = line
+                .as_bytes()
+                .chunks(3)
+                .map(|chunk| String::from_utf8(chunk.to_vec()).unwrap())
+                .collect();
+            assert_eq!(compartments, expected);
+        }
+        assert!(read_rucksacks_contents(
+            "abc\n",
+            0,
+            &PriorityTable::default(),
+            &mut Diagnostics::default()
+        )
+        .is_err());
+        assert!(read_rucksacks_contents(
+            "abcd\n",
+            3,
+            &PriorityTable::default(),
+            &mut Diagnostics::default()
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn shared_by_two_or_by_all_compartments() {
+        for count in 2..=6 {
+            // Every compartment holds Z, the first two y, and one item of
+            // their own.
+            let line: String = (0..count)
+                .flat_map(|index| {
+                    let own = (b'a' + index as u8) as char;
+                    let pair = if index < 2 {
+                        'y'
+                    } else {
+                        own.to_ascii_uppercase()
+                    };
+                    ['Z', own, pair]
+                })
+                .collect();
+            let rucksacks = read_rucksacks_contents(
+                &line,
+                count,
+                &PriorityTable::default(),
+                &mut Diagnostics::default(),
+            )
+            .unwrap();
+            let rucksack = &rucksacks[0];
+
+            assert_eq!(
+                rucksack.packing_errors(&PriorityTable::default(), SharedBy::AtLeastTwo),
+                ['y', 'Z']
+            );
+            let all: &[char] = if count == 2 { &['y', 'Z'] } else { &['Z'] };
+            assert_eq!(
+                rucksack.packing_errors(&PriorityTable::default(), SharedBy::All),
+                all,
+                "{count}"
+            );
+            assert_eq!(
+                rucksack.get_packing_errors_priority_anysize(
+                    &PriorityTable::default(),
+                    SharedBy::AtLeastTwo
+                ),
+                25 + 52
+            );
+        }
+    }
+
+    #[test]
+    fn single_compartment_rucksacks_have_no_packing_errors() {
+        let rucksacks = read_rucksacks_contents(
+            "aab\n",
+            1,
+            &PriorityTable::default(),
+            &mut Diagnostics::default(),
+        )
+        .unwrap();
+
+        assert_eq!(rucksacks[0].get_packing_errors_priority(), 0);
+    }
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(solve_example(&Day3, EXAMPLE, Part::One), "157");
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(solve_example(&Day3, EXAMPLE, Part::Two), "70");
+    }
 }
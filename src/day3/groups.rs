@@ -0,0 +1,130 @@
+use super::{add_priorities, item_set::ItemSet, priorities::PriorityTable, Rucksack};
+use crate::error::{Error, Result};
+
+/// Elves per group in the puzzle.
+pub const GROUP_SIZE: usize = 3;
+
+/// The one item every rucksack of `group` carries. `group_number` is the
+/// 1-based number used in errors.
+pub fn find_badge(table: &PriorityTable, group_number: usize, group: &[Rucksack]) -> Result<char> {
+    let common = group
+        .iter()
+        .map(|rucksack| ItemSet::from_items(table, rucksack.items()))
+        .reduce(ItemSet::intersection)
+        .unwrap_or_default();
+    let common = match group.first() {
+        Some(rucksack) => rucksack.items_in(table, common),
+        None => Vec::new(),
+    };
+
+    match common.as_slice() {
+        [badge] => Ok(*badge),
+        [] => Err(Error::validation(format!(
+            "Group {group_number} has no item in common"
+        ))),
+        items => Err(Error::validation(format!(
+            "Group {group_number} has {} items in common: {}",
+            items.len(),
+            items.iter().collect::<String>()
+        ))),
+    }
+}
+
+/// The badge of every group of `group_size` consecutive rucksacks.
+pub fn find_badges(
+    table: &PriorityTable,
+    rucksacks: &[Rucksack],
+    group_size: usize,
+) -> Result<Vec<char>> {
+    if group_size == 0 {
+        return Err(Error::validation("Groups need at least one rucksack"));
+    }
+    if !rucksacks.len().is_multiple_of(group_size) {
+        return Err(Error::validation(format!(
+            "{} rucksacks cannot be split into groups of {group_size}",
+            rucksacks.len()
+        )));
+    }
+
+    rucksacks
+        .chunks_exact(group_size)
+        .enumerate()
+        .map(|(index, group)| find_badge(table, index + 1, group))
+        .collect()
+}
+
+/// Sum of the priorities of every group's badge.
+pub fn badge_priorities(
+    table: &PriorityTable,
+    rucksacks: &[Rucksack],
+    group_size: usize,
+) -> Result<u64> {
+    find_badges(table, rucksacks, group_size)?
+        .iter()
+        .try_fold(0, |total, &badge| {
+            add_priorities(total, u64::from(table.priority(badge).unwrap_or_default()))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        day3::{read_rucksacks_contents, tests::EXAMPLE},
+        diagnostics::Diagnostics,
+    };
+
+    fn rucksacks(input: &str) -> Vec<Rucksack> {
+        read_rucksacks_contents(
+            input,
+            2,
+            &PriorityTable::default(),
+            &mut Diagnostics::default(),
+        )
+        .unwrap()
+    }
+
+    fn find_badges(rucksacks: &[Rucksack], group_size: usize) -> Result<Vec<char>> {
+        super::find_badges(&PriorityTable::default(), rucksacks, group_size)
+    }
+
+    #[test]
+    fn finds_one_badge_per_group() {
+        let rucksacks = rucksacks(EXAMPLE);
+
+        assert_eq!(find_badges(&rucksacks, GROUP_SIZE).unwrap(), ['r', 'Z']);
+        assert_eq!(
+            badge_priorities(&PriorityTable::default(), &rucksacks, GROUP_SIZE).unwrap(),
+            70
+        );
+    }
+
+    #[test]
+    fn group_size_is_configurable() {
+        let rucksacks = rucksacks("abcd\naefg\nhbij\nbklm\n");
+
+        assert_eq!(find_badges(&rucksacks, 2).unwrap(), ['a', 'b']);
+        assert!(find_badges(&rucksacks, 3).is_err());
+        assert!(find_badges(&rucksacks, 0).is_err());
+    }
+
+    #[test]
+    fn rejects_groups_without_exactly_one_common_item() {
+        let error = find_badges(&rucksacks("ab\nbc\nab\ncd\n"), 2).unwrap_err();
+        assert_eq!(error.to_string(), "Group 2 has no item in common");
+
+        let error = find_badges(&rucksacks("abcd\ndcba\n"), 2).unwrap_err();
+        assert_eq!(error.to_string(), "Group 1 has 4 items in common: abcd");
+    }
+
+    #[test]
+    fn badges_with_custom_priorities() {
+        let table = PriorityTable::from_alphabet("0123456789€").unwrap();
+        let rucksacks =
+            read_rucksacks_contents("01€2\n€345\n", 2, &table, &mut Diagnostics::default())
+                .unwrap();
+
+        assert_eq!(super::find_badges(&table, &rucksacks, 2).unwrap(), ['€']);
+        assert_eq!(badge_priorities(&table, &rucksacks, 2).unwrap(), 11);
+    }
+}
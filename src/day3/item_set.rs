@@ -0,0 +1,180 @@
+use std::ops::{BitAnd, BitOr};
+
+use super::priorities::PriorityTable;
+
+/// A set of items stored as one bit per position in a [`PriorityTable`], so
+/// unions and intersections are single instructions.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ItemSet(u64);
+
+impl ItemSet {
+    /// Most items a table can have for its items to fit in a set.
+    pub const CAPACITY: usize = u64::BITS as usize;
+
+    pub const fn new() -> ItemSet {
+        ItemSet(0)
+    }
+
+    /// The set of `items`. Items missing from `table` are left out.
+    pub fn from_items(table: &PriorityTable, items: impl IntoIterator<Item = char>) -> ItemSet {
+        let mut set = ItemSet::new();
+        for item in items {
+            set.insert(table, item);
+        }
+        set
+    }
+
+    /// Adds `item`, returning whether `table` has it.
+    pub fn insert(&mut self, table: &PriorityTable, item: char) -> bool {
+        match table.position(item) {
+            Some(position) => {
+                self.0 |= 1 << position;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn contains(&self, table: &PriorityTable, item: char) -> bool {
+        match table.position(item) {
+            Some(position) => self.0 & (1 << position) != 0,
+            None => false,
+        }
+    }
+
+    pub fn union(self, other: ItemSet) -> ItemSet {
+        ItemSet(self.0 | other.0)
+    }
+
+    pub fn intersection(self, other: ItemSet) -> ItemSet {
+        ItemSet(self.0 & other.0)
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Table positions of the items in the set, lowest first.
+    pub fn positions(self) -> impl Iterator<Item = usize> {
+        let mut bits = self.0;
+        std::iter::from_fn(move || match bits {
+            0 => None,
+            _ => {
+                let position = bits.trailing_zeros() as usize;
+                bits &= bits - 1;
+                Some(position)
+            }
+        })
+    }
+
+    /// Priorities of the items in the set, in table order.
+    pub fn priorities(self, table: &PriorityTable) -> impl Iterator<Item = u32> + '_ {
+        self.positions().map(|position| table.priority_at(position))
+    }
+
+    /// Cannot overflow, as a set holds at most 64 priorities of 32 bits.
+    pub fn priority_sum(self, table: &PriorityTable) -> u64 {
+        self.priorities(table).map(u64::from).sum()
+    }
+}
+
+impl BitOr for ItemSet {
+    type Output = ItemSet;
+
+    fn bitor(self, other: ItemSet) -> ItemSet {
+        self.union(other)
+    }
+}
+
+impl BitAnd for ItemSet {
+    type Output = ItemSet;
+
+    fn bitand(self, other: ItemSet) -> ItemSet {
+        self.intersection(other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::{
+        day3::{priorities::read_priority_table, Priority},
+        test_util::Xorshift,
+    };
+
+    fn random_items(seed: u64, count: usize) -> Vec<char> {
+        let items: Vec<char> = ('a'..='z').chain('A'..='Z').collect();
+        let mut random = Xorshift::new(seed);
+
+        (0..count)
+            .map(|_| items[random.below(items.len())])
+            .collect()
+    }
+
+    fn priorities(items: &HashSet<char>) -> Vec<u32> {
+        let mut priorities: Vec<u32> = items.iter().filter_map(char::to_priority).collect();
+        priorities.sort();
+        priorities
+    }
+
+    #[test]
+    fn insert_and_contains() {
+        let table = PriorityTable::default();
+        let mut set = ItemSet::new();
+
+        assert!(set.is_empty());
+        assert!(set.insert(&table, 'a'));
+        assert!(set.insert(&table, 'Z'));
+        assert!(set.insert(&table, 'a'));
+        assert!(!set.insert(&table, '1'));
+        assert_eq!(set.len(), 2);
+        assert!(set.contains(&table, 'Z'));
+        assert!(!set.contains(&table, 'b'));
+        assert_eq!(set.priorities(&table).collect::<Vec<_>>(), [1, 52]);
+        assert_eq!(set.priority_sum(&table), 53);
+    }
+
+    #[test]
+    fn holds_custom_priorities() {
+        let table = read_priority_table("€ 100\n0 5\n1 5\n").unwrap();
+        let set = ItemSet::from_items(&table, ['€', '1', 'a', '1']);
+
+        assert_eq!(set.positions().collect::<Vec<_>>(), [0, 2]);
+        assert_eq!(set.priority_sum(&table), 105);
+        assert!(set.contains(&table, '€'));
+        assert!(!set.contains(&table, 'a'));
+    }
+
+    #[test]
+    fn agrees_with_hash_sets() {
+        for seed in 1..=50 {
+            let (a, b) = (random_items(seed, 20), random_items(seed + 1000, 20));
+            let (a_set, b_set) = (
+                ItemSet::from_items(&PriorityTable::Aoc, a.iter().copied()),
+                ItemSet::from_items(&PriorityTable::Aoc, b.iter().copied()),
+            );
+            let (a_hash, b_hash): (HashSet<char>, HashSet<char>) =
+                (a.iter().copied().collect(), b.iter().copied().collect());
+
+            assert_eq!(a_set.len(), a_hash.len());
+            assert_eq!(
+                (a_set | b_set)
+                    .priorities(&PriorityTable::Aoc)
+                    .collect::<Vec<_>>(),
+                priorities(&a_hash.union(&b_hash).copied().collect())
+            );
+            assert_eq!(
+                (a_set & b_set)
+                    .priorities(&PriorityTable::Aoc)
+                    .collect::<Vec<_>>(),
+                priorities(&a_hash.intersection(&b_hash).copied().collect())
+            );
+        }
+    }
+}
@@ -0,0 +1,168 @@
+use std::collections::HashMap;
+
+use super::item_set::ItemSet;
+use crate::error::{Error, Result};
+
+/// Where items get their priorities from. Every item of a table also has a
+/// position, which is its bit in an [`ItemSet`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum PriorityTable {
+    /// The puzzle's scheme: `a` to `z` are 1 to 26, `A` to `Z` 27 to 52.
+    #[default]
+    Aoc,
+    /// Priorities given item by item, built with [`PriorityTable::custom`].
+    Custom(CustomTable),
+}
+
+/// Priorities given item by item, in table order. Only
+/// [`PriorityTable::custom`] builds one, so every position fits an
+/// [`ItemSet`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CustomTable {
+    items: Vec<(char, u32)>,
+    positions: HashMap<char, usize>,
+}
+
+impl PriorityTable {
+    /// Checks no item is listed twice and that there are no more items than
+    /// an [`ItemSet`] holds. Priorities are free: items may share one.
+    pub fn custom(items: Vec<(char, u32)>) -> Result<PriorityTable> {
+        if items.len() > ItemSet::CAPACITY {
+            return Err(Error::validation(format!(
+                "A priority table holds at most {} items",
+                ItemSet::CAPACITY
+            )));
+        }
+
+        let mut positions = HashMap::new();
+        for (position, &(item, _)) in items.iter().enumerate() {
+            if positions.insert(item, position).is_some() {
+                return Err(Error::validation(format!("{item} is listed twice")));
+            }
+        }
+
+        Ok(PriorityTable::Custom(CustomTable { items, positions }))
+    }
+
+    /// Gives the items of `alphabet` priorities 1, 2, 3 and so on.
+    pub fn from_alphabet(alphabet: &str) -> Result<PriorityTable> {
+        PriorityTable::custom(alphabet.chars().zip(1..).collect())
+    }
+
+    /// 0-based position of `item` in the table.
+    pub fn position(&self, item: char) -> Option<usize> {
+        match self {
+            PriorityTable::Aoc => match item {
+                'a'..='z' => Some(item as usize - 'a' as usize),
+                'A'..='Z' => Some(item as usize - 'A' as usize + 26),
+                _ => None,
+            },
+            PriorityTable::Custom(table) => table.positions.get(&item).copied(),
+        }
+    }
+
+    /// Priority of the item at `position`.
+    pub fn priority_at(&self, position: usize) -> u32 {
+        match self {
+            PriorityTable::Aoc => position as u32 + 1,
+            PriorityTable::Custom(table) => table.items[position].1,
+        }
+    }
+
+    pub fn priority(&self, item: char) -> Option<u32> {
+        self.position(item)
+            .map(|position| self.priority_at(position))
+    }
+}
+
+/// Reads a priority table with one `<item> <priority>` pair per line. Blank
+/// lines are ignored.
+pub fn read_priority_table(input: &str) -> Result<PriorityTable> {
+    let mut items = Vec::new();
+
+    for (line_index, line) in input.lines().enumerate() {
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        let (item, priority) = match tokens.as_slice() {
+            [] => continue,
+            [item, priority] => (*item, *priority),
+            _ => {
+                return Err(Error::parse(
+                    line_index + 1,
+                    line,
+                    "Expected an item and its priority",
+                ))
+            }
+        };
+
+        let error =
+            |token: &str, message: &str| Error::parse_token(line_index + 1, line, token, message);
+        let mut chars = item.chars();
+        let item_char = match (chars.next(), chars.next()) {
+            (Some(item_char), None) => item_char,
+            _ => return Err(error(item, "An item is a single char")),
+        };
+        let priority_value = priority
+            .parse::<u32>()
+            .map_err(|_| error(priority, "Priority is not a number"))?;
+        if items.iter().any(|&(listed, _)| listed == item_char) {
+            return Err(error(item, "Item is listed twice"));
+        }
+        items.push((item_char, priority_value));
+    }
+
+    PriorityTable::custom(items)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aoc_scheme() {
+        let table = PriorityTable::default();
+
+        assert_eq!(table.priority('a'), Some(1));
+        assert_eq!(table.priority('Z'), Some(52));
+        assert_eq!(table.priority('7'), None);
+    }
+
+    #[test]
+    fn digits_and_symbols() {
+        let table = PriorityTable::from_alphabet("0123456789αβγ€").unwrap();
+
+        assert_eq!(table.priority('0'), Some(1));
+        assert_eq!(table.priority('γ'), Some(13));
+        assert_eq!(table.priority('€'), Some(14));
+        assert_eq!(table.priority('a'), None);
+        assert!(PriorityTable::from_alphabet("abca").is_err());
+
+        let too_many: String = ('a'..='z')
+            .chain('A'..='Z')
+            .chain('0'..='9')
+            .chain("αβγ".chars())
+            .collect();
+        assert!(PriorityTable::from_alphabet(&too_many).is_err());
+        assert!(PriorityTable::from_alphabet(&too_many[1..]).is_ok());
+    }
+
+    #[test]
+    fn reads_priority_tables() {
+        let table = read_priority_table("α 10\n\nß 2\n# 63\n").unwrap();
+
+        assert_eq!(table.priority('α'), Some(10));
+        assert_eq!(table.priority('ß'), Some(2));
+        assert_eq!(table.priority('#'), Some(63));
+
+        let table = read_priority_table("a 100\nb 5\nc 5\n").unwrap();
+        assert_eq!(table.priority('a'), Some(100));
+        assert_eq!(
+            (table.priority('b'), table.priority('c')),
+            (Some(5), Some(5))
+        );
+        assert_eq!(table.position('c'), Some(2));
+
+        assert!(read_priority_table("ab 1\n").is_err());
+        assert!(read_priority_table("a one\n").is_err());
+        assert!(read_priority_table("a 1\na 2\n").is_err());
+    }
+}
@@ -0,0 +1,435 @@
+use std::{cmp::Reverse, collections::HashMap};
+
+use super::{Compartment, Rucksack};
+use crate::error::{Error, Result};
+
+/// Moving `count` of `item` between two compartments, by their positions in
+/// [`Rucksack::compartments`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Move {
+    pub item: char,
+    pub count: usize,
+    pub from: usize,
+    pub to: usize,
+}
+
+/// Moves that leave every item in a single compartment, with compartment
+/// sizes unchanged.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Repacking {
+    pub moves: Vec<Move>,
+}
+
+impl Repacking {
+    /// Number of items moved.
+    pub fn moved_items(&self) -> usize {
+        self.moves.iter().map(|item_move| item_move.count).sum()
+    }
+}
+
+/// One kind of item in a rucksack and how many of it each compartment holds.
+struct ItemKind {
+    item: char,
+    counts: Vec<usize>,
+    total: usize,
+}
+
+/// Branch and bound over the compartment of every kind of two or more
+/// items. Single items are interchangeable, so they are only counted.
+struct Search<'a> {
+    kinds: Vec<&'a ItemKind>,
+    size: usize,
+    /// Single items in each compartment.
+    singles: Vec<usize>,
+    /// `bounds[n]` is the most items kinds from the nth on could keep, each
+    /// in the compartment holding most of it.
+    bounds: Vec<usize>,
+    fills: Vec<usize>,
+    targets: Vec<usize>,
+    /// Most items kept on reaching each fill of the compartments, once the
+    /// kinds before the nth are placed.
+    visited: HashMap<(usize, Vec<usize>), usize>,
+    /// Items kept by the best placement found, and its compartments.
+    best: Option<(usize, Vec<usize>)>,
+}
+
+impl<'a> Search<'a> {
+    fn new(kinds: Vec<&'a ItemKind>, singles: Vec<usize>, size: usize) -> Search<'a> {
+        let mut bounds = vec![0; kinds.len() + 1];
+        for (index, kind) in kinds.iter().enumerate().rev() {
+            bounds[index] = bounds[index + 1] + kind.counts.iter().max().unwrap();
+        }
+
+        Search {
+            size,
+            bounds,
+            fills: vec![0; singles.len()],
+            singles,
+            targets: vec![0; kinds.len()],
+            visited: HashMap::new(),
+            best: None,
+            kinds,
+        }
+    }
+
+    /// Single items that can stay where they are once every other kind is
+    /// placed: as many as there is room for, the rest filling the gaps
+    /// elsewhere. Fills only grow, so before that it is an upper bound.
+    fn singles_kept(&self) -> usize {
+        self.singles
+            .iter()
+            .zip(self.fills.iter())
+            .map(|(&singles, &fill)| singles.min(self.size.saturating_sub(fill)))
+            .sum()
+    }
+
+    /// The most items kinds from the `index`th on could keep, each in the
+    /// compartment holding most of it among those it still fits in.
+    fn bound(&self, index: usize) -> usize {
+        self.kinds[index..]
+            .iter()
+            .map(|kind| {
+                (0..self.fills.len())
+                    .filter(|&target| self.fills[target] + kind.total <= self.size)
+                    .map(|target| kind.counts[target])
+                    .max()
+                    .unwrap_or(0)
+            })
+            .sum()
+    }
+
+    /// Places the kinds from the `index`th on, `kept` items being kept so
+    /// far.
+    fn place(&mut self, index: usize, kept: usize) {
+        let singles_kept = self.singles_kept();
+        if let Some((best_kept, _)) = &self.best {
+            if kept + self.bounds[index] + singles_kept <= *best_kept
+                || kept + self.bound(index) + singles_kept <= *best_kept
+            {
+                return;
+            }
+        }
+        if index == self.kinds.len() {
+            self.best = Some((kept + singles_kept, self.targets.clone()));
+            return;
+        }
+        // An earlier visit to the same fills, keeping as many, already
+        // explored every placement from here.
+        match self.visited.get_mut(&(index, self.fills.clone())) {
+            Some(visited_kept) if *visited_kept >= kept => return,
+            Some(visited_kept) => *visited_kept = kept,
+            None => {
+                self.visited.insert((index, self.fills.clone()), kept);
+            }
+        }
+
+        let kind = self.kinds[index];
+        let mut targets: Vec<usize> = (0..self.fills.len())
+            .filter(|&target| self.fills[target] + kind.total <= self.size)
+            .collect();
+        targets.sort_by_key(|&target| Reverse(kind.counts[target]));
+
+        for (position, &target) in targets.iter().enumerate() {
+            // Compartments equally full, holding as many singles and as many
+            // of every kind left, lead to the same placements.
+            if targets[..position].iter().any(|&other| {
+                self.fills[other] == self.fills[target]
+                    && self.singles[other] == self.singles[target]
+                    && self.kinds[index..]
+                        .iter()
+                        .all(|kind| kind.counts[other] == kind.counts[target])
+            }) {
+                continue;
+            }
+
+            self.fills[target] += kind.total;
+            self.targets[index] = target;
+            self.place(index + 1, kept + kind.counts[target]);
+            self.fills[target] -= kind.total;
+        }
+    }
+}
+
+impl Rucksack {
+    /// The fewest item moves making the compartments disjoint while keeping
+    /// them equally sized. Every kind of item goes to one compartment, so
+    /// this picks the compartments that keep the most items in place.
+    pub fn repacking(&self) -> Result<Repacking> {
+        let compartment_count = self.compartments.len();
+        let size = self
+            .compartments
+            .first()
+            .map_or(0, |compartment| compartment.contents.len());
+        if self
+            .compartments
+            .iter()
+            .any(|compartment| compartment.contents.len() != size)
+        {
+            return Err(Error::validation("Compartments are not equally sized"));
+        }
+
+        let mut kinds: Vec<ItemKind> = Vec::new();
+        for (index, compartment) in self.compartments.iter().enumerate() {
+            for &item in compartment.contents.iter() {
+                let kind = match kinds.iter().position(|kind| kind.item == item) {
+                    Some(position) => &mut kinds[position],
+                    None => {
+                        kinds.push(ItemKind {
+                            item,
+                            counts: vec![0; compartment_count],
+                            total: 0,
+                        });
+                        kinds.last_mut().unwrap()
+                    }
+                };
+                kind.counts[index] += 1;
+                kind.total += 1;
+            }
+        }
+
+        if kinds.iter().any(|kind| kind.total > size) {
+            return Err(Error::validation(
+                "No repacking makes the compartments disjoint",
+            ));
+        }
+
+        // Placing the biggest kinds first finds dead ends early.
+        let mut order: Vec<usize> = (0..kinds.len())
+            .filter(|&index| kinds[index].total > 1)
+            .collect();
+        order.sort_by_key(|&index| Reverse(kinds[index].total));
+        let mut singles = vec![0; compartment_count];
+        for kind in kinds.iter().filter(|kind| kind.total == 1) {
+            singles[kind.counts.iter().position(|&count| count == 1).unwrap()] += 1;
+        }
+
+        let mut search = Search::new(
+            order.iter().map(|&index| &kinds[index]).collect(),
+            singles,
+            size,
+        );
+        search.place(0, 0);
+        let best_targets = match search.best {
+            Some((_, best_targets)) => best_targets,
+            None => {
+                return Err(Error::validation(
+                    "No repacking makes the compartments disjoint",
+                ))
+            }
+        };
+
+        let mut targets: Vec<Option<usize>> = vec![None; kinds.len()];
+        let mut room = vec![size; compartment_count];
+        for (&index, target) in order.iter().zip(best_targets) {
+            targets[index] = Some(target);
+            room[target] -= kinds[index].total;
+        }
+        // Singles stay while their compartment has room, the rest fill the
+        // room left in the others.
+        let single_kinds: Vec<usize> = (0..kinds.len())
+            .filter(|&index| kinds[index].total == 1)
+            .collect();
+        for &index in single_kinds.iter() {
+            let home = kinds[index]
+                .counts
+                .iter()
+                .position(|&count| count == 1)
+                .unwrap();
+            if room[home] > 0 {
+                targets[index] = Some(home);
+                room[home] -= 1;
+            }
+        }
+        for &index in single_kinds.iter() {
+            if targets[index].is_none() {
+                let target = room.iter().position(|&room| room > 0).unwrap();
+                targets[index] = Some(target);
+                room[target] -= 1;
+            }
+        }
+        let targets = targets.into_iter().map(Option::unwrap);
+
+        let moves = kinds
+            .iter()
+            .zip(targets)
+            .flat_map(|(kind, to)| {
+                kind.counts
+                    .iter()
+                    .enumerate()
+                    .filter(move |&(from, &count)| from != to && count > 0)
+                    .map(move |(from, &count)| Move {
+                        item: kind.item,
+                        count,
+                        from,
+                        to,
+                    })
+            })
+            .collect();
+
+        Ok(Repacking { moves })
+    }
+
+    /// The rucksack after `repacking`, moved items added at the end of their
+    /// new compartment.
+    pub fn repacked(&self, repacking: &Repacking) -> Rucksack {
+        let mut compartments: Vec<Vec<char>> = self
+            .compartments
+            .iter()
+            .map(|compartment| compartment.contents.clone())
+            .collect();
+
+        for item_move in repacking.moves.iter() {
+            for _ in 0..item_move.count {
+                let from = &mut compartments[item_move.from];
+                if let Some(position) = from.iter().position(|&item| item == item_move.item) {
+                    from.remove(position);
+                    compartments[item_move.to].push(item_move.item);
+                }
+            }
+        }
+
+        Rucksack {
+            compartments: compartments
+                .into_iter()
+                .map(|contents| Compartment { contents })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::test_util::Xorshift;
+
+    fn rucksack(compartments: &[&str]) -> Rucksack {
+        Rucksack {
+            compartments: compartments
+                .iter()
+                .map(|contents| Compartment {
+                    contents: contents.chars().collect(),
+                })
+                .collect(),
+        }
+    }
+
+    fn assert_disjoint_and_equal(rucksack: &Rucksack, size: usize) {
+        let mut seen: HashSet<char> = HashSet::new();
+        for compartment in rucksack.compartments.iter() {
+            assert_eq!(compartment.contents.len(), size);
+            let items: HashSet<char> = compartment.contents.iter().copied().collect();
+            assert!(seen.is_disjoint(&items), "{items:?} overlaps {seen:?}");
+            seen.extend(items);
+        }
+    }
+
+    /// The fewest items to move, trying every compartment for every kind.
+    fn brute_force_moved_items(rucksack: &Rucksack) -> Option<usize> {
+        let compartment_count = rucksack.compartments.len();
+        let size = rucksack.compartments[0].contents.len();
+        let mut kinds: Vec<char> = rucksack.items().collect();
+        kinds.sort();
+        kinds.dedup();
+
+        let count = |compartment: usize, kind: char| {
+            rucksack.compartments[compartment]
+                .contents
+                .iter()
+                .filter(|&&item| item == kind)
+                .count()
+        };
+        let total = |kind: char| rucksack.items().filter(|&item| item == kind).count();
+
+        (0..compartment_count.pow(kinds.len() as u32))
+            .filter_map(|assignment| {
+                let targets: Vec<usize> = (0..kinds.len())
+                    .map(|index| {
+                        assignment / compartment_count.pow(index as u32) % compartment_count
+                    })
+                    .collect();
+                let mut fill = vec![0; compartment_count];
+                let mut kept = 0;
+                for (&kind, &target) in kinds.iter().zip(targets.iter()) {
+                    fill[target] += total(kind);
+                    kept += count(target, kind);
+                }
+                fill.iter()
+                    .all(|&fill| fill == size)
+                    .then(|| compartment_count * size - kept)
+            })
+            .min()
+    }
+
+    #[test]
+    fn disjoint_rucksacks_need_no_moves() {
+        assert_eq!(
+            rucksack(&["abc", "def"]).repacking().unwrap(),
+            Repacking::default()
+        );
+    }
+
+    #[test]
+    fn swaps_a_shared_item_for_one_of_the_same_size() {
+        let rucksack = rucksack(&["ab", "bc"]);
+        let repacking = rucksack.repacking().unwrap();
+
+        assert_eq!(repacking.moved_items(), 2);
+        assert_disjoint_and_equal(&rucksack.repacked(&repacking), 2);
+    }
+
+    #[test]
+    fn rejects_rucksacks_that_cannot_be_repacked() {
+        assert!(rucksack(&["aa", "ab"]).repacking().is_err());
+        assert!(rucksack(&["ab", "a"]).repacking().is_err());
+    }
+
+    #[test]
+    fn moves_as_few_items_as_brute_force() {
+        let mut random = Xorshift::new(0x2022_0025);
+        let mut next = move |bound: usize| random.below(bound);
+
+        let mut repacked = 0;
+        for _ in 0..300 {
+            let compartment_count = 2 + next(3);
+            let size = 1 + next(4);
+            let compartments: Vec<String> = (0..compartment_count)
+                .map(|_| (0..size).map(|_| (b'a' + next(5) as u8) as char).collect())
+                .collect();
+            let compartments: Vec<&str> = compartments.iter().map(String::as_str).collect();
+            let rucksack = rucksack(&compartments);
+
+            match (rucksack.repacking(), brute_force_moved_items(&rucksack)) {
+                (Ok(repacking), Some(moved_items)) => {
+                    assert_eq!(repacking.moved_items(), moved_items, "{compartments:?}");
+                    assert_disjoint_and_equal(&rucksack.repacked(&repacking), size);
+                    repacked += 1;
+                }
+                (Err(_), None) => (),
+                (repacking, moved_items) => {
+                    panic!("{compartments:?}: {repacking:?} but brute force {moved_items:?}")
+                }
+            }
+        }
+        assert!(repacked > 0);
+    }
+
+    #[test]
+    fn repacks_puzzle_sized_input_into_six_compartments() {
+        let mut random = Xorshift::new(0x2022_0006);
+        let mut next = move |bound: usize| random.below(bound);
+        let items: Vec<char> = ('a'..='z').chain('A'..='Z').collect();
+
+        for _ in 0..300 {
+            let compartments: Vec<String> = (0..6)
+                .map(|_| (0..8).map(|_| items[next(items.len())]).collect())
+                .collect();
+            let compartments: Vec<&str> = compartments.iter().map(String::as_str).collect();
+            let rucksack = rucksack(&compartments);
+
+            let repacking = rucksack.repacking().unwrap();
+            assert_disjoint_and_equal(&rucksack.repacked(&repacking), 8);
+        }
+    }
+}
@@ -1,13 +1,17 @@
-use std::{fs::read_to_string, ops::ControlFlow, path::Path};
+use crate::{
+    diagnostics::Diagnostics,
+    error::{Error, Result},
+    solution::{Solution, Unsolved},
+};
 
 #[derive(Debug)]
-struct ElfSectionRange {
-    range_start: u32,
-    range_end: u32,
+pub struct ElfSectionRange {
+    pub range_start: u32,
+    pub range_end: u32,
 }
 
 impl ElfSectionRange {
-    fn contains(&self, other: &Self) -> bool {
+    pub fn contains(&self, other: &Self) -> bool {
         let contains_start = self.range_start <= other.range_start;
         let contains_end = self.range_end >= other.range_end;
         contains_start && contains_end
@@ -15,99 +19,135 @@ impl ElfSectionRange {
 }
 
 #[derive(Debug)]
-struct ElfGroup {
-    elf_section_ranges: Vec<ElfSectionRange>,
+pub struct ElfGroup {
+    pub elf_section_ranges: Vec<ElfSectionRange>,
 }
 
 impl ElfGroup {
-    fn has_one_range_fully_contain_another(&self) -> bool {
-        let mut contains = false;
+    pub fn has_one_range_fully_contain_another(&self) -> bool {
         self.elf_section_ranges
             .iter()
             .enumerate()
-            .try_for_each(|(index, section_range_1)| {
+            .any(|(index, section_range_1)| {
                 self.elf_section_ranges
                     .iter()
                     .skip(index + 1)
-                    .try_for_each(|section_range_2| {
-                        if section_range_1.contains(section_range_2)
+                    .any(|section_range_2| {
+                        section_range_1.contains(section_range_2)
                             || section_range_2.contains(section_range_1)
-                        {
-                            contains = true;
-                            return ControlFlow::Break(());
-                        }
-                        ControlFlow::Continue(())
-                    });
-
-                if contains {
-                    return ControlFlow::Break(());
-                } else {
-                    return ControlFlow::Continue(());
-                }
-            });
-
-        contains
+                    })
+            })
     }
 }
 
-fn read_elf_groups(path: &Path) -> Result<Vec<ElfGroup>, &str> {
-    let lines = if let Ok(lines) = read_to_string(path) {
-        lines
-    } else {
-        return Err("Error reading file");
+fn read_elf_group(line_index: usize, line: &str) -> Result<ElfGroup> {
+    let error =
+        |token: &str, message: &str| Error::parse_token(line_index + 1, line, token, message);
+    let read_section = |section: &str| {
+        if section.is_empty() || !section.chars().all(|char| char.is_ascii_digit()) {
+            return Err(error(section, "Section is not a number"));
+        }
+        section
+            .parse::<u32>()
+            .map_err(|_| error(section, "Section is out of range"))
+    };
+
+    let mut elf_group = ElfGroup {
+        elf_section_ranges: Vec::new(),
     };
+
+    for range in line.split(',') {
+        let (range_start, range_end) = range
+            .split_once('-')
+            .ok_or_else(|| error(range, "Expected a section range like \"2-4\""))?;
+        let range_start = read_section(range_start)?;
+        let range_end = read_section(range_end)?;
+
+        if range_start > range_end {
+            return Err(error(range, "Section range ends before it starts"));
+        }
+
+        elf_group.elf_section_ranges.push(ElfSectionRange {
+            range_start,
+            range_end,
+        });
+    }
+
+    Ok(elf_group)
+}
+
+pub fn read_elf_groups(input: &str, diagnostics: &mut Diagnostics) -> Result<Vec<ElfGroup>> {
     let mut elf_groups: Vec<ElfGroup> = Vec::new();
-    for line in lines.lines() {
-        let mut elf_group = ElfGroup {
-            elf_section_ranges: Vec::new(),
-        };
-        let mut reading_range_start = true;
-        let mut range_start: u32 = 0;
-        let mut range_end: u32 = 0;
-
-        line.chars()
-            .chain([','].into_iter())
-            .for_each(|char| match char {
-                '0'..='9' => {
-                    if let Some(digit) = char.to_digit(10) {
-                        if reading_range_start {
-                            range_start *= 10;
-                            range_start += digit;
-                        } else {
-                            range_end *= 10;
-                            range_end += digit;
-                        }
-                    }
-                }
-                '-' | ',' => {
-                    reading_range_start = !reading_range_start;
-                    if char == ',' {
-                        elf_group.elf_section_ranges.push(ElfSectionRange {
-                            range_start,
-                            range_end,
-                        });
-                        range_start = 0;
-                        range_end = 0;
-                    }
-                }
-                _ => (),
-            });
-
-        elf_groups.push(elf_group);
+    for (line_index, line) in input.lines().enumerate() {
+        match read_elf_group(line_index, line) {
+            Ok(elf_group) => elf_groups.push(elf_group),
+            Err(error) => diagnostics.reject(error)?,
+        }
     }
 
     Ok(elf_groups)
 }
 
-pub fn day4() {
-    let elf_groups = read_elf_groups(Path::new("elf-groups.txt")).unwrap();
-    let contains_count = elf_groups.iter().fold(0, |mut sum, elf_group| {
-        if elf_group.has_one_range_fully_contain_another() {
-            sum += 1;
+pub struct Day4;
+
+impl Solution for Day4 {
+    type Puzzle = Vec<ElfGroup>;
+    type Answer1 = u32;
+    type Answer2 = Unsolved;
+
+    fn parse(input: &str, diagnostics: &mut Diagnostics) -> Result<Vec<ElfGroup>> {
+        read_elf_groups(input, diagnostics)
+    }
+
+    fn part1(elf_groups: &Vec<ElfGroup>) -> Result<u32> {
+        Ok(elf_groups.iter().fold(0, |mut sum, elf_group| {
+            if elf_group.has_one_range_fully_contain_another() {
+                sum += 1;
+            }
+
+            sum
+        }))
+    }
+
+    fn part2(_: &Vec<ElfGroup>) -> Result<Unsolved> {
+        Err(Error::Unsolved)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::{solve_example, Part};
+
+    const EXAMPLE: &str = "\
+2-4,6-8
+2-3,4-5
+5-7,7-9
+2-8,3-7
+6-6,4-6
+2-6,4-8
+";
+
+    #[test]
+    fn rejects_ranges_that_end_before_they_start() {
+        let error = read_elf_groups("2-4,8-6\n", &mut Diagnostics::default()).unwrap_err();
+
+        match error {
+            Error::Parse(parse_error) => {
+                assert_eq!((parse_error.column, parse_error.width), (5, 3))
+            }
+            error => panic!("expected a parse error, got {error:?}"),
         }
+    }
 
-        sum
-    });
+    #[test]
+    fn part1_example() {
+        assert_eq!(solve_example(&Day4, EXAMPLE, Part::One), "2");
+    }
 
-    println!("{contains_count}");
+    #[test]
+    #[ignore = "part 2 is not solved yet"]
+    fn part2_example() {
+        assert_eq!(solve_example(&Day4, EXAMPLE, Part::Two), "4");
+    }
 }
@@ -1,24 +1,29 @@
-use std::{fs::read_to_string, ops::ControlFlow, path::Path};
-
-#[derive(Debug)]
-struct CrateStack {
-    crates: Vec<char>,
+use crate::{
+    diagnostics::Diagnostics,
+    error::{Error, Result},
+    solution::{Solution, Unsolved},
+};
+
+#[derive(Clone, Debug)]
+pub struct CrateStack {
+    pub crates: Vec<char>,
 }
 
-#[derive(Debug)]
-struct CraneInstruction {
-    origin_stack: usize,
-    destination_stack: usize,
-    crates_to_move: usize,
+#[derive(Clone, Debug)]
+pub struct CraneInstruction {
+    pub origin_stack: usize,
+    pub destination_stack: usize,
+    pub crates_to_move: usize,
 }
 
-struct Ship {
-    crate_stacks: Vec<CrateStack>,
-    crane_instructions: Vec<CraneInstruction>,
+#[derive(Clone)]
+pub struct Ship {
+    pub crate_stacks: Vec<CrateStack>,
+    pub crane_instructions: Vec<CraneInstruction>,
 }
 
 impl Ship {
-    fn execute_instructions(&mut self) {
+    pub fn execute_instructions(&mut self) {
         self.crane_instructions
             .iter()
             .for_each(|crane_instruction| {
@@ -37,11 +42,10 @@ impl Ship {
                         }
                     }
                 }
-                println!("{:?}", self.crate_stacks);
             });
     }
 
-    fn tops_of_stacks(&self) -> Vec<&char> {
+    pub fn tops_of_stacks(&self) -> Vec<&char> {
         let mut tops_of_stacks = Vec::new();
         self.crate_stacks.iter().for_each(|crate_stack| {
             if let Some(crate_unit) = crate_stack.crates.last() {
@@ -52,101 +56,199 @@ impl Ship {
     }
 }
 
-fn read_ship_state_and_instructions(path: &Path) -> Result<Ship, &str> {
-    let lines = if let Ok(lines) = read_to_string(path) {
-        lines
-    } else {
-        return Err("Error reading file");
+fn read_crate_row(
+    line_index: usize,
+    line: &str,
+    crate_stacks: &mut Vec<CrateStack>,
+    diagnostics: &mut Diagnostics,
+) -> Result<()> {
+    if line
+        .chars()
+        .all(|char| char == ' ' || char.is_ascii_digit())
+    {
+        let stack_count = line.split_whitespace().count();
+        if crate_stacks.len() < stack_count {
+            crate_stacks.resize_with(stack_count, || CrateStack { crates: Vec::new() });
+        }
+        return Ok(());
+    }
+
+    let chars: Vec<char> = line.chars().collect();
+    for (stack_index, cell) in chars.chunks(4).enumerate() {
+        let crate_unit = match cell {
+            ['[', crate_unit, ']'] | ['[', crate_unit, ']', ' ']
+                if crate_unit.is_ascii_uppercase() =>
+            {
+                *crate_unit
+            }
+            cell if cell.iter().all(|char| *char == ' ') => continue,
+            cell => {
+                diagnostics.reject(
+                    Error::parse(
+                        line_index + 1,
+                        line,
+                        "Expected a crate like \"[A]\" or an empty slot",
+                    )
+                    .at(stack_index * 4 + 1, cell.len().min(3)),
+                )?;
+                continue;
+            }
+        };
+
+        if crate_stacks.len() <= stack_index {
+            crate_stacks.resize_with(stack_index + 1, || CrateStack { crates: Vec::new() });
+        }
+        crate_stacks[stack_index].crates.insert(0, crate_unit);
+    }
+
+    Ok(())
+}
+
+fn read_crane_instruction(
+    line_index: usize,
+    line: &str,
+    stack_count: usize,
+) -> Result<CraneInstruction> {
+    let error =
+        |token: &str, message: String| Error::parse_token(line_index + 1, line, token, message);
+    let read_number = |token: &str| {
+        if !token.chars().all(|char| char.is_ascii_digit()) {
+            return Err(error(token, "Expected a number".to_string()));
+        }
+        token
+            .parse::<usize>()
+            .map_err(|_| error(token, "Number is out of range".to_string()))
+    };
+    let read_stack = |token: &str| {
+        let stack = read_number(token)?;
+        if stack == 0 || stack > stack_count {
+            return Err(error(
+                token,
+                format!("Stack {stack} does not exist, stacks are numbered 1 to {stack_count}"),
+            ));
+        }
+        Ok(stack)
     };
 
+    let tokens: Vec<&str> = line.split_whitespace().collect();
+    match tokens.as_slice() {
+        ["move", crates_to_move, "from", origin_stack, "to", destination_stack] => {
+            Ok(CraneInstruction {
+                crates_to_move: read_number(crates_to_move)?,
+                origin_stack: read_stack(origin_stack)?,
+                destination_stack: read_stack(destination_stack)?,
+            })
+        }
+        _ => Err(Error::parse(
+            line_index + 1,
+            line,
+            "Expected an instruction like \"move 1 from 2 to 3\"",
+        )),
+    }
+}
+
+pub fn read_ship_state_and_instructions(
+    input: &str,
+    diagnostics: &mut Diagnostics,
+) -> Result<Ship> {
     let mut reached_instructions = false;
 
     let mut crate_stacks: Vec<CrateStack> = Vec::new();
     let mut crane_instructions: Vec<CraneInstruction> = Vec::new();
 
-    lines.lines().into_iter().try_for_each(|line| {
-        if line.len() == 0 {
+    for (line_index, line) in input.lines().enumerate() {
+        if line.is_empty() {
             reached_instructions = true;
-            return ControlFlow::<()>::Continue(());
+            continue;
         }
 
-        if crate_stacks.len() == 0 {
-            for _ in 0..=(line.len() / 4) {
-                crate_stacks.push(CrateStack { crates: Vec::new() });
+        if !reached_instructions {
+            read_crate_row(line_index, line, &mut crate_stacks, diagnostics)?;
+        } else {
+            match read_crane_instruction(line_index, line, crate_stacks.len()) {
+                Ok(crane_instruction) => crane_instructions.push(crane_instruction),
+                Err(error) => diagnostics.reject(error)?,
             }
         }
+    }
 
-        if !reached_instructions {
-            let mut morpheme_index = 0;
-            let mut token_index = 0;
-
-            line.chars().for_each(|char| {
-                if morpheme_index == 1 && ('A'..='Z').contains(&char) {
-                    if let Some(crate_stack) = crate_stacks.get_mut(token_index) {
-                        if !(char == ' ') {
-                            crate_stack.crates.insert(0, char);
-                        }
-                    };
-                }
+    Ok(Ship {
+        crane_instructions,
+        crate_stacks,
+    })
+}
 
-                morpheme_index += 1;
-                if morpheme_index % 4 == 0 {
-                    token_index += 1;
-                    morpheme_index = 0;
-                }
-            });
-        } else {
-            let mut crates_to_move: usize = 0;
-            let mut origin_stack: usize = 0;
-            let mut destination_stack: usize = 0;
+pub struct Day5;
 
-            let mut num_buffer: usize = 0;
+impl Solution for Day5 {
+    type Puzzle = Ship;
+    type Answer1 = String;
+    type Answer2 = Unsolved;
 
-            line.chars().chain([' '].into_iter()).for_each(|char| {
-                if ('0'..='9').contains(&char) {
-                    if let Some(digit) = char.to_digit(10) {
-                        num_buffer *= 10;
-                        num_buffer += digit as usize;
-                    }
-                } else {
-                    if crates_to_move == 0 {
-                        crates_to_move = num_buffer;
-                    } else if origin_stack == 0 {
-                        origin_stack = num_buffer;
-                    } else if destination_stack == 0 {
-                        destination_stack = num_buffer;
-                    }
+    fn parse(input: &str, diagnostics: &mut Diagnostics) -> Result<Ship> {
+        read_ship_state_and_instructions(input, diagnostics)
+    }
 
-                    num_buffer = 0;
-                }
-            });
+    fn part1(ship: &Ship) -> Result<String> {
+        let mut ship = ship.clone();
 
-            crane_instructions.push(CraneInstruction {
-                crates_to_move,
-                origin_stack,
-                destination_stack,
-            });
-        }
+        ship.execute_instructions();
 
-        ControlFlow::Continue(())
-    });
+        Ok(ship.tops_of_stacks().into_iter().collect())
+    }
 
-    return Ok(Ship {
-        crane_instructions,
-        crate_stacks,
-    });
+    fn part2(_: &Ship) -> Result<Unsolved> {
+        Err(Error::Unsolved)
+    }
 }
 
-pub fn day5() {
-    let mut ship =
-        read_ship_state_and_instructions(Path::new("ship-state-and-instructions.txt")).unwrap();
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::{solve_example, Part};
+
+    const EXAMPLE: &str = "    [D]    
+[N] [C]    
+[Z] [M] [P]
+ 1   2   3 
+
+move 1 from 2 to 1
+move 3 from 1 to 3
+move 2 from 2 to 1
+move 1 from 1 to 2
+";
+
+    #[test]
+    fn reads_stacks_bottom_to_top_and_instructions() {
+        let ship = read_ship_state_and_instructions(EXAMPLE, &mut Diagnostics::default()).unwrap();
 
-    println!("{:?}", ship.crate_stacks);
-    println!("{:?}", ship.crane_instructions);
+        let stacks: Vec<String> = ship
+            .crate_stacks
+            .iter()
+            .map(|crate_stack| crate_stack.crates.iter().collect())
+            .collect();
+        assert_eq!(stacks, ["ZN", "MCD", "P"]);
+        assert_eq!(ship.crane_instructions.len(), 4);
+        assert_eq!(ship.crane_instructions[1].crates_to_move, 3);
+        assert_eq!(ship.crane_instructions[1].origin_stack, 1);
+        assert_eq!(ship.crane_instructions[1].destination_stack, 3);
+    }
+
+    #[test]
+    fn rejects_instructions_naming_missing_stacks() {
+        let input = "[A]\n 1 \n\nmove 1 from 1 to 2\n";
 
-    ship.execute_instructions();
+        assert!(read_ship_state_and_instructions(input, &mut Diagnostics::default()).is_err());
+    }
 
-    let tops_of_stacks = ship.tops_of_stacks();
+    #[test]
+    fn part1_example() {
+        assert_eq!(solve_example(&Day5, EXAMPLE, Part::One), "CMZ");
+    }
 
-    println!("{:?}", tops_of_stacks);
+    #[test]
+    #[ignore = "part 2 is not solved yet"]
+    fn part2_example() {
+        assert_eq!(solve_example(&Day5, EXAMPLE, Part::Two), "MCD");
+    }
 }
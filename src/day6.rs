@@ -1,24 +1,31 @@
-use std::{collections::HashSet, fs::read_to_string, path::Path};
+use std::collections::HashSet;
 
-fn read_signals(path: &Path) -> Result<Vec<String>, &str> {
-    let lines = if let Ok(lines) = read_to_string(path) {
-        lines
-    } else {
-        return Err("Error reading file.");
-    };
+use crate::{
+    diagnostics::Diagnostics,
+    error::{Error, Result},
+    solution::{Solution, Unsolved},
+};
 
-    let mut codes: Vec<String> = Vec::new();
-    lines.lines().into_iter().for_each(|line| {
-        codes.push(line.to_string());
-    });
+pub fn read_signals(input: &str, diagnostics: &mut Diagnostics) -> Result<Vec<String>> {
+    let mut signals: Vec<String> = Vec::new();
 
-    Ok(codes)
+    for (line_index, line) in input.lines().enumerate() {
+        match line.chars().position(|char| !char.is_ascii_lowercase()) {
+            None if !line.is_empty() => signals.push(line.to_string()),
+            None => diagnostics.reject(Error::parse(line_index + 1, line, "A signal is empty"))?,
+            Some(column_index) => diagnostics.reject(
+                Error::parse(line_index + 1, line, "Expected a lowercase letter")
+                    .at(column_index + 1, 1),
+            )?,
+        }
+    }
+
+    Ok(signals)
 }
 
-fn index_after_marker(signal: &String) -> usize {
+pub fn index_after_marker(signal: &str) -> Option<usize> {
     signal
         .char_indices()
-        .into_iter()
         .try_fold(Vec::new(), |mut marker, (index, char)| {
             marker.insert(0, char);
 
@@ -38,14 +45,67 @@ fn index_after_marker(signal: &String) -> usize {
 
             Ok(marker)
         })
-        .unwrap_err()
+        .err()
 }
 
-pub fn day6() {
-    let signals = read_signals(Path::new("signals.txt")).unwrap();
+pub struct Day6;
+
+impl Solution for Day6 {
+    type Puzzle = Vec<String>;
+    type Answer1 = String;
+    type Answer2 = Unsolved;
+
+    fn parse(input: &str, diagnostics: &mut Diagnostics) -> Result<Vec<String>> {
+        read_signals(input, diagnostics)
+    }
+
+    fn part1(signals: &Vec<String>) -> Result<String> {
+        let indices = signals
+            .iter()
+            .enumerate()
+            .map(|(line, signal)| {
+                index_after_marker(signal)
+                    .map(|index| index.to_string())
+                    .ok_or_else(|| {
+                        Error::validation(format!("Signal on line {} has no marker", line + 1))
+                    })
+            })
+            .collect::<Result<Vec<String>>>()?;
+
+        Ok(indices.join(","))
+    }
+
+    fn part2(_: &Vec<String>) -> Result<Unsolved> {
+        Err(Error::Unsolved)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::{solve_example, Part};
+
+    const EXAMPLE: &str = "\
+mjqjpqmgbljsphdztnvjfqwrcgsmlb
+bvwbjplbgvbhsrlpgdmjqwftvncz
+nppdvjthqldpwncqszvftbrmjlhg
+nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg
+zcfzfwzzqfrljwzlrfnpqdbhtmscgvjwzr
+";
+
+    #[test]
+    fn signal_without_marker_has_no_index() {
+        assert_eq!(index_after_marker("aaaaaa"), None);
+    }
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(solve_example(&Day6, EXAMPLE, Part::One), "7,5,6,10,11");
+    }
 
-    signals.iter().for_each(|signal| {
-        let index = index_after_marker(signal);
-        println!("{index}");
-    });
+    #[test]
+    #[ignore = "part 2 is not solved yet"]
+    fn part2_example() {
+        assert_eq!(solve_example(&Day6, EXAMPLE, Part::Two), "19,23,23,29,26");
+    }
 }
@@ -1,11 +1,16 @@
+use builder::{Builder, Built};
 use std::cell::RefCell;
 use std::rc::Rc;
-use std::{fs::read_to_string, io::Error, path::Path};
-use builder::{Built, Builder};
+
+use crate::{
+    diagnostics::{column_of, Diagnostics},
+    error::{Error, Result},
+    solution::{Solution, Unsolved},
+};
 
 #[derive(Debug)]
-struct ChangeDirectory {
-    name: String,
+pub struct ChangeDirectory {
+    pub name: String,
 }
 
 impl Built for ChangeDirectory {
@@ -17,12 +22,12 @@ impl Built for ChangeDirectory {
 }
 
 #[derive(Debug)]
-struct ChangeDirectoryBuilder {
+pub struct ChangeDirectoryBuilder {
     name: Option<String>,
 }
 
 impl ChangeDirectoryBuilder {
-    fn name(&mut self, name: String) -> &mut Self {
+    pub fn name(&mut self, name: String) -> &mut Self {
         self.name = Some(name);
         self
     }
@@ -36,19 +41,13 @@ impl Builder for ChangeDirectoryBuilder {
     }
 
     fn build(self) -> Option<ChangeDirectory> {
-        if let Some(name) = self.name {
-            Some(ChangeDirectory {
-                name: name.to_string(),
-            })
-        } else {
-            None
-        }
+        self.name.map(|name| ChangeDirectory { name })
     }
 }
 
 #[derive(Debug)]
-struct ListDirectory {
-    directory_items: Vec<DirectoryItem>,
+pub struct ListDirectory {
+    pub directory_items: Vec<DirectoryItem>,
 }
 
 impl Built for ListDirectory {
@@ -62,7 +61,7 @@ impl Built for ListDirectory {
 }
 
 #[derive(Debug)]
-struct ListDirectoryBuilder {
+pub struct ListDirectoryBuilder {
     directory_items: Vec<Option<DirectoryItem>>,
 }
 
@@ -81,24 +80,20 @@ impl Builder for ListDirectoryBuilder {
         }
 
         Some(ListDirectory {
-            directory_items: self
-                .directory_items
-                .into_iter()
-                .filter_map(|item| item)
-                .collect(),
+            directory_items: self.directory_items.into_iter().flatten().collect(),
         })
     }
 }
 
 #[derive(Debug)]
-struct Directory {
-    name: String,
-    directory_items: Vec<DirectoryItem>,
-    size: Option<u32>,
+pub struct Directory {
+    pub name: String,
+    pub directory_items: Vec<DirectoryItem>,
+    pub size: Option<u32>,
 }
 
 impl Directory {
-    fn child_directory(&mut self, name: &str) -> Option<Rc<RefCell<Directory>>> {
+    pub fn child_directory(&mut self, name: &str) -> Option<Rc<RefCell<Directory>>> {
         self.directory_items.iter_mut().find_map(|item| match item {
             DirectoryItem::Directory(directory) => {
                 if directory.borrow().name == name {
@@ -114,20 +109,20 @@ impl Directory {
 
 #[derive(Debug)]
 #[allow(dead_code)]
-struct File {
-    name: String,
-    size: u32,
-    extension: Option<String>,
+pub struct File {
+    pub name: String,
+    pub size: u32,
+    pub extension: Option<String>,
 }
 
 #[derive(Debug)]
-enum DirectoryItem {
+pub enum DirectoryItem {
     Directory(Rc<RefCell<Directory>>),
     File(File),
 }
 
 #[derive(Debug)]
-struct FileBuilder {
+pub struct FileBuilder {
     name: Option<String>,
     size: Option<u32>,
     extension: Option<String>,
@@ -142,17 +137,17 @@ impl Built for File {
 }
 
 impl FileBuilder {
-    fn name(&mut self, name: String) -> &mut Self {
+    pub fn name(&mut self, name: String) -> &mut Self {
         self.name = Some(name);
         self
     }
 
-    fn size(&mut self, size: u32) -> &mut Self {
+    pub fn size(&mut self, size: u32) -> &mut Self {
         self.size = Some(size);
         self
     }
 
-    fn extension(&mut self, extension: Option<String>) -> &mut Self {
+    pub fn extension(&mut self, extension: Option<String>) -> &mut Self {
         self.extension = extension;
         self
     }
@@ -183,7 +178,7 @@ impl Builder for FileBuilder {
 }
 
 #[derive(Debug)]
-enum TerminalCommand {
+pub enum TerminalCommand {
     ChangeDirectory(ChangeDirectory),
     ListDirectory(ListDirectory),
     Invalid(String),
@@ -195,20 +190,24 @@ enum TerminalCommandBuilder {
     ListDirectoryBuilder(ListDirectoryBuilder),
 }
 
-fn read_terminal(path: &Path) -> Result<Vec<String>, Error> {
-    let lines = read_to_string(path)?;
-
-    let owned_lines = lines.lines().map(|line| line.to_owned()).collect();
-
-    Ok(owned_lines)
+pub fn read_terminal(input: &str) -> Result<Vec<String>> {
+    Ok(input.lines().map(|line| line.to_owned()).collect())
 }
 
-fn lex_terminal_lines(lines: Vec<String>) -> Vec<TerminalCommand> {
+pub fn lex_terminal_lines(
+    lines: Vec<String>,
+    diagnostics: &mut Diagnostics,
+) -> Result<Vec<TerminalCommand>> {
     let mut terminal_commands: Vec<TerminalCommand> = Vec::new();
     let mut current_command_builder: Option<TerminalCommandBuilder> = None;
 
-    for line in lines.iter() {
+    for (line_index, line) in lines.iter().enumerate() {
         let mut tokens_iter = line.split(" ");
+        let error = |message: &str| Error::parse(line_index + 1, line, message);
+        let listing = matches!(
+            current_command_builder,
+            Some(TerminalCommandBuilder::ListDirectoryBuilder(_))
+        );
 
         let mut token = match tokens_iter.next() {
             Some(token) => token,
@@ -246,6 +245,7 @@ fn lex_terminal_lines(lines: Vec<String>) -> Vec<TerminalCommand> {
             token = match tokens_iter.next() {
                 Some(token) => token,
                 None => {
+                    diagnostics.reject(error("Expected a command after $"))?;
                     continue;
                 }
             };
@@ -266,6 +266,7 @@ fn lex_terminal_lines(lines: Vec<String>) -> Vec<TerminalCommand> {
 
                 match name {
                     None => {
+                        diagnostics.reject(error("Expected a directory name after cd"))?;
                         current_command_builder = None;
                         terminal_commands
                             .push(TerminalCommand::Invalid(format!("{}", line!()).to_string()));
@@ -293,10 +294,15 @@ fn lex_terminal_lines(lines: Vec<String>) -> Vec<TerminalCommand> {
                     }
                 }
             }
+            "dir" if !listing => {
+                diagnostics.reject(error("Directory listed outside of ls output"))?;
+                continue;
+            }
             "dir" => {
                 let name = tokens_iter.next();
                 match name {
                     None => {
+                        diagnostics.reject(error("Expected a directory name after dir"))?;
                         current_command_builder = if let Some(builder) = current_command_builder {
                             Some(
                                 if let TerminalCommandBuilder::ListDirectoryBuilder(mut builder) =
@@ -336,8 +342,24 @@ fn lex_terminal_lines(lines: Vec<String>) -> Vec<TerminalCommand> {
                     }
                 }
             }
-            token if token.chars().all(char::is_numeric) => {
-                let size = token.parse::<u32>().unwrap();
+            token if !token.is_empty() && token.chars().all(|char| char.is_ascii_digit()) => {
+                if !listing {
+                    diagnostics.reject(error("File listed outside of ls output"))?;
+                    continue;
+                }
+
+                let size = match token.parse::<u32>() {
+                    Ok(size) => size,
+                    Err(_) => {
+                        diagnostics.reject(Error::parse_token(
+                            line_index + 1,
+                            line,
+                            token,
+                            "File size is out of range",
+                        ))?;
+                        continue;
+                    }
+                };
 
                 let mut file_builder = File::builder();
                 file_builder.size(size);
@@ -346,6 +368,7 @@ fn lex_terminal_lines(lines: Vec<String>) -> Vec<TerminalCommand> {
 
                 match name_token {
                     None => {
+                        diagnostics.reject(error("Expected a file name after its size"))?;
                         current_command_builder = None;
                         terminal_commands
                             .push(TerminalCommand::Invalid(format!("{}", line!()).to_string()));
@@ -355,10 +378,8 @@ fn lex_terminal_lines(lines: Vec<String>) -> Vec<TerminalCommand> {
 
                         if let Some(name) = name_tokens.next() {
                             file_builder.name(name.to_string());
-                            let extension = match name_tokens.next() {
-                                Some(extension) => Some(extension.to_string()),
-                                None => None,
-                            };
+                            let extension =
+                                name_tokens.next().map(|extension| extension.to_string());
                             file_builder.extension(extension);
                             let file = file_builder.build();
                             if let Some(file) = file {
@@ -390,7 +411,18 @@ fn lex_terminal_lines(lines: Vec<String>) -> Vec<TerminalCommand> {
                     }
                 }
             }
-            _ => {}
+            _ => {
+                diagnostics.reject(error("Expected a command or ls output"))?;
+                continue;
+            }
+        }
+
+        if let Some(extra) = tokens_iter.next() {
+            let column = column_of(line, extra);
+            diagnostics.reject(
+                error("Unexpected text at the end of the line")
+                    .at(column, line.chars().count() + 1 - column),
+            )?;
         }
     }
 
@@ -417,10 +449,10 @@ fn lex_terminal_lines(lines: Vec<String>) -> Vec<TerminalCommand> {
         }
     }
 
-    terminal_commands
+    Ok(terminal_commands)
 }
 
-fn get_directories_from_commands(commands: &mut Vec<TerminalCommand>) -> Rc<RefCell<Directory>> {
+pub fn get_directories_from_commands(commands: &mut [TerminalCommand]) -> Rc<RefCell<Directory>> {
     let root_directory = Rc::new(RefCell::new(Directory {
         name: "/".to_string(),
         directory_items: Vec::new(),
@@ -471,11 +503,9 @@ fn get_directories_from_commands(commands: &mut Vec<TerminalCommand>) -> Rc<RefC
                     &mut directory_stack.last().unwrap().borrow_mut().directory_items;
                 let list_directory_items = &mut list_directory.directory_items;
 
-                while directory_items.len() > 0 {
-                    directory_items.pop();
-                }
-                while list_directory_items.len() > 0 {
-                    directory_items.push(list_directory_items.pop().unwrap());
+                directory_items.clear();
+                while let Some(item) = list_directory_items.pop() {
+                    directory_items.push(item);
                 }
             }
             TerminalCommand::Invalid(_) => {}
@@ -485,29 +515,37 @@ fn get_directories_from_commands(commands: &mut Vec<TerminalCommand>) -> Rc<RefC
     root_directory
 }
 
-fn calc_directory_sizes(directories: Rc<RefCell<Directory>>) {
-    let size = calc_directory_size(Rc::clone(&directories));
+fn add_sizes(total: u32, size: u32) -> Result<u32> {
+    total
+        .checked_add(size)
+        .ok_or_else(|| Error::validation("Directory size does not fit in 32 bits"))
+}
+
+pub fn calc_directory_sizes(directories: Rc<RefCell<Directory>>) -> Result<()> {
+    let size = calc_directory_size(Rc::clone(&directories))?;
     directories.borrow_mut().size = Some(size);
+    Ok(())
 }
 
-fn calc_directory_size(directories: Rc<RefCell<Directory>>) -> u32 {
+pub fn calc_directory_size(directories: Rc<RefCell<Directory>>) -> Result<u32> {
     let mut size: u32 = 0;
 
     for directory_item in directories.borrow().directory_items.iter() {
-        size += match directory_item {
+        let item_size = match directory_item {
             DirectoryItem::Directory(directory) => {
-                let dir_size = calc_directory_size(Rc::clone(directory));
+                let dir_size = calc_directory_size(Rc::clone(directory))?;
                 directory.borrow_mut().size = Some(dir_size);
                 dir_size
             }
             DirectoryItem::File(file) => file.size,
-        }
+        };
+        size = add_sizes(size, item_size)?;
     }
 
-    size
+    Ok(size)
 }
 
-fn get_directories_under_size(
+pub fn get_directories_under_size(
     directories: Rc<RefCell<Directory>>,
     size: u32,
 ) -> Vec<Rc<RefCell<Directory>>> {
@@ -520,37 +558,133 @@ fn get_directories_under_size(
     }
 
     for directory in directories.borrow().directory_items.iter() {
-        match directory {
-            DirectoryItem::Directory(directory) => {
-                let mut child_directories = get_directories_under_size(Rc::clone(&directory), size);
+        if let DirectoryItem::Directory(directory) = directory {
+            let mut child_directories = get_directories_under_size(Rc::clone(directory), size);
 
-                collected_directories.append(&mut child_directories);
-            }
-            _ => (),
+            collected_directories.append(&mut child_directories);
         }
     }
 
     collected_directories
 }
 
-pub fn day7() {
-    let lines = read_terminal(Path::new("terminal-output.txt")).unwrap();
+pub struct Day7;
 
-    let mut commands = lex_terminal_lines(lines);
+impl Solution for Day7 {
+    type Puzzle = Rc<RefCell<Directory>>;
+    type Answer1 = u32;
+    type Answer2 = Unsolved;
 
-    let directories = get_directories_from_commands(&mut commands);
+    fn parse(input: &str, diagnostics: &mut Diagnostics) -> Result<Rc<RefCell<Directory>>> {
+        let mut commands = lex_terminal_lines(read_terminal(input)?, diagnostics)?;
 
-    calc_directory_sizes(Rc::clone(&directories));
+        let directories = get_directories_from_commands(&mut commands);
 
-    let collected_directories = get_directories_under_size(Rc::clone(&directories), 100_000);
+        calc_directory_sizes(Rc::clone(&directories))?;
 
-    let sum = collected_directories.iter().fold(0, |mut sum, directory| {
-        if let Some(size) = directory.borrow().size {
-            sum += size;
-        }
+        Ok(directories)
+    }
 
-        sum
-    });
+    fn part1(directories: &Rc<RefCell<Directory>>) -> Result<u32> {
+        let collected_directories = get_directories_under_size(Rc::clone(directories), 100_000);
+
+        collected_directories
+            .iter()
+            .try_fold(0, |sum, directory| match directory.borrow().size {
+                Some(size) => add_sizes(sum, size),
+                None => Ok(sum),
+            })
+    }
+
+    fn part2(_: &Rc<RefCell<Directory>>) -> Result<Unsolved> {
+        Err(Error::Unsolved)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::{solve_example, Part};
+
+    const EXAMPLE: &str = "\
+$ cd /
+$ ls
+dir a
+14848514 b.txt
+8504156 c.dat
+dir d
+$ cd a
+$ ls
+dir e
+29116 f
+2557 g
+62596 h.lst
+$ cd e
+$ ls
+584 i
+$ cd ..
+$ cd ..
+$ cd d
+$ ls
+4060174 j
+8033020 d.log
+5626152 d.ext
+7214296 k
+";
+
+    #[test]
+    fn lexes_one_command_per_prompt() {
+        let commands =
+            lex_terminal_lines(read_terminal(EXAMPLE).unwrap(), &mut Diagnostics::default())
+                .unwrap();
+
+        let kinds: Vec<&str> = commands
+            .iter()
+            .map(|command| match command {
+                TerminalCommand::ChangeDirectory(_) => "cd",
+                TerminalCommand::ListDirectory(_) => "ls",
+                TerminalCommand::Invalid(_) => "invalid",
+            })
+            .collect();
+        assert_eq!(
+            kinds,
+            ["cd", "ls", "cd", "ls", "cd", "ls", "cd", "cd", "cd", "ls"]
+        );
+    }
 
-    println!("{sum}");
+    #[test]
+    fn rejects_listing_output_outside_ls() {
+        let lines = read_terminal("$ cd /\n123 a.txt\n").unwrap();
+
+        assert!(lex_terminal_lines(lines, &mut Diagnostics::default()).is_err());
+    }
+
+    #[test]
+    fn rejects_directories_too_big_to_size() {
+        let input = "$ cd /\n$ ls\n4294967295 a\n1 b\n";
+
+        assert!(matches!(
+            Day7::parse(input, &mut Diagnostics::default()),
+            Err(Error::Validation(_))
+        ));
+    }
+
+    #[test]
+    fn sizes_directories_recursively() {
+        let mut diagnostics = Diagnostics::default();
+        let root = Day7::parse(EXAMPLE, &mut diagnostics).unwrap();
+
+        assert_eq!(root.borrow().size, Some(48381165));
+    }
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(solve_example(&Day7, EXAMPLE, Part::One), "95437");
+    }
+
+    #[test]
+    #[ignore = "part 2 is not solved yet"]
+    fn part2_example() {
+        assert_eq!(solve_example(&Day7, EXAMPLE, Part::Two), "24933642");
+    }
 }
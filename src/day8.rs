@@ -1,52 +1,63 @@
-use std::{fs::read_to_string, path::Path};
-use builder::{Built, Builder};
+use builder::{Builder, Built};
 use builder_derive::Builder;
 
+use crate::{
+    diagnostics::Diagnostics,
+    error::{Error, Result},
+    solution::{Solution, Unsolved},
+};
+
 #[derive(Builder, Debug)]
-struct Tree {
-    height: u32,
-    visible_left: bool,
-    visible_right: bool,
-    visible_top: bool,
-    visible_bottom: bool
+pub struct Tree {
+    pub height: u32,
+    pub visible_left: bool,
+    pub visible_right: bool,
+    pub visible_top: bool,
+    pub visible_bottom: bool,
 }
 
 #[derive(Debug)]
-struct Field {
-    trees: Vec<u32>,
-    height: u32,
-    width: u32
+pub struct Field {
+    pub trees: Vec<u32>,
+    pub height: u32,
+    pub width: u32,
 }
 
 #[derive(Debug)]
-struct Coords {
-    row: u32,
-    column: u32
+pub struct Coords {
+    pub row: u32,
+    pub column: u32,
 }
 
 impl Field {
-    fn get_column(&self, index: u32) -> u32 {
+    pub fn get_column(&self, index: u32) -> u32 {
         index % self.width
     }
 
-    fn get_row(&self, index: u32) -> u32 {
+    pub fn get_row(&self, index: u32) -> u32 {
         index / self.width
     }
 
-    fn get_coords(&self, index: u32) -> Coords {
-        Coords { row: self.get_row(index), column: self.get_column(index) }
+    pub fn get_coords(&self, index: u32) -> Coords {
+        Coords {
+            row: self.get_row(index),
+            column: self.get_column(index),
+        }
     }
 
-    fn get_index(&self, coords: Coords) -> u32 {
+    pub fn get_index(&self, coords: Coords) -> u32 {
         (coords.row * self.width) + coords.column
     }
 
-    fn visible_left(&self, index: u32) -> Option<bool> {
+    pub fn visible_left(&self, index: u32) -> Option<bool> {
         let coords = self.get_coords(index);
         let eval_tree_height = self.trees.get(index as usize)?;
 
-        for column in (0..coords.column).into_iter() {
-            let tree_height = self.trees.get(self.get_index(Coords { row: coords.row, column }) as usize)?;
+        for column in 0..coords.column {
+            let tree_height = self.trees.get(self.get_index(Coords {
+                row: coords.row,
+                column,
+            }) as usize)?;
             if tree_height >= eval_tree_height {
                 return Some(false);
             }
@@ -55,12 +66,15 @@ impl Field {
         Some(true)
     }
 
-    fn visible_right(&self, index: u32) -> Option<bool> {
+    pub fn visible_right(&self, index: u32) -> Option<bool> {
         let coords = self.get_coords(index);
         let eval_tree_height = self.trees.get(index as usize)?;
-        
+
         for column in ((coords.column + 1)..self.width).rev() {
-            let tree_height = self.trees.get(self.get_index(Coords { row: coords.row, column }) as usize)?;
+            let tree_height = self.trees.get(self.get_index(Coords {
+                row: coords.row,
+                column,
+            }) as usize)?;
             if tree_height >= eval_tree_height {
                 return Some(false);
             }
@@ -69,12 +83,15 @@ impl Field {
         Some(true)
     }
 
-    fn visible_bottom(&self, index: u32) -> Option<bool> {
+    pub fn visible_bottom(&self, index: u32) -> Option<bool> {
         let coords = self.get_coords(index);
         let eval_tree_height = self.trees.get(index as usize)?;
-        
+
         for row in ((coords.row + 1)..self.height).rev() {
-            let tree_height = self.trees.get(self.get_index(Coords { row, column: coords.column }) as usize)?;
+            let tree_height = self.trees.get(self.get_index(Coords {
+                row,
+                column: coords.column,
+            }) as usize)?;
             if tree_height >= eval_tree_height {
                 return Some(false);
             }
@@ -83,12 +100,15 @@ impl Field {
         Some(true)
     }
 
-    fn visible_top(&self, index: u32) -> Option<bool> {
+    pub fn visible_top(&self, index: u32) -> Option<bool> {
         let coords = self.get_coords(index);
         let eval_tree_height = self.trees.get(index as usize)?;
 
-        for row in (0..coords.row).into_iter() {
-            let tree_height = self.trees.get(self.get_index(Coords { row, column: coords.column }) as usize)?;
+        for row in 0..coords.row {
+            let tree_height = self.trees.get(self.get_index(Coords {
+                row,
+                column: coords.column,
+            }) as usize)?;
             if tree_height >= eval_tree_height {
                 return Some(false);
             }
@@ -97,57 +117,87 @@ impl Field {
         Some(true)
     }
 
-    fn calc_visibilities(&self, index: u32) -> Option<Tree> {
+    pub fn calc_visibilities(&self, index: u32) -> Option<Tree> {
         let tree_builder = Tree::builder();
 
         let height = self.trees.get(index as usize)?;
-            
+
         tree_builder
             .height(height.to_owned())
-            .visible_left(self.visible_left(index as u32)?)
-            .visible_right(self.visible_right(index as u32)?)
-            .visible_top(self.visible_top(index as u32)?)
-            .visible_bottom(self.visible_bottom(index as u32)?)
+            .visible_left(self.visible_left(index)?)
+            .visible_right(self.visible_right(index)?)
+            .visible_top(self.visible_top(index)?)
+            .visible_bottom(self.visible_bottom(index)?)
             .build()
     }
 }
 
-fn read_tree_heights(path: &Path) -> Result<Field, String> {
-    let lines = read_to_string(path).map_err(|_| "Error reading file".to_string())?;
+/// Reads one row of digits per line. The first valid row sets the width of
+/// the field, and later rows of another width are rejected.
+pub fn read_tree_heights(lines: &str, diagnostics: &mut Diagnostics) -> Result<Field> {
+    if lines.is_empty() {
+        return Err(Error::validation("The tree field is empty"));
+    }
 
     let mut field = Field {
         trees: Vec::new(),
-        height: lines.lines().count() as u32,
-        width: lines.lines().next().unwrap().chars().count() as u32
+        height: 0,
+        width: 0,
     };
-    
-    for line in lines.lines().into_iter() {
-        for char in line.chars().into_iter() {
-            let height = char.to_digit(10).ok_or("An input char is not a digit.".to_string())?;
-            field.trees.push(height);
+
+    for (line_index, line) in lines.lines().enumerate() {
+        let width = line.chars().count() as u32;
+        if field.height == 0 && width == 0 {
+            diagnostics.reject(Error::parse(line_index + 1, line, "Row has no trees"))?;
+            continue;
+        }
+        if field.height > 0 && width != field.width {
+            diagnostics.reject(Error::parse(
+                line_index + 1,
+                line,
+                format!("Row is not {} trees wide", field.width),
+            ))?;
+            continue;
+        }
+
+        let heights: Option<Vec<u32>> = line.chars().map(|char| char.to_digit(10)).collect();
+        match heights {
+            Some(mut heights) => {
+                field.trees.append(&mut heights);
+                field.width = width;
+                field.height += 1;
+            }
+            None => {
+                let column_index = line.chars().position(|char| !char.is_ascii_digit());
+                diagnostics.reject(
+                    Error::parse(line_index + 1, line, "An input char is not a digit.")
+                        .at(column_index.unwrap_or_default() + 1, 1),
+                )?;
+            }
         }
     }
-    
+
+    if field.height == 0 {
+        return Err(Error::validation("The tree field has no valid rows"));
+    }
+
     Ok(field)
 }
 
-fn calc_tree_visibilities(field: &mut Field) -> Option<Vec<Tree>> {
+pub fn calc_tree_visibilities(field: &Field) -> Option<Vec<Tree>> {
     let mut trees = Vec::new();
     for (index, _) in field.trees.iter().enumerate() {
         trees.push(field.calc_visibilities(index as u32)?);
-    };
+    }
 
     Some(trees)
 }
 
-fn visible_tree_count(trees: Vec<Tree>) -> Option<u32> {
+pub fn visible_tree_count(trees: Vec<Tree>) -> Option<u32> {
     let mut count: u32 = 0;
-    
+
     for tree in trees.iter() {
-        if tree.visible_left ||
-            tree.visible_right ||
-            tree.visible_top ||
-            tree.visible_bottom {
+        if tree.visible_left || tree.visible_right || tree.visible_top || tree.visible_bottom {
             count += 1;
         }
     }
@@ -155,9 +205,90 @@ fn visible_tree_count(trees: Vec<Tree>) -> Option<u32> {
     Some(count)
 }
 
-pub fn day8() {
-    let mut field = read_tree_heights(Path::new("tree-heights.txt")).unwrap();
-    let trees = calc_tree_visibilities(&mut field).unwrap();    
-    let count = visible_tree_count(trees).unwrap();
-    println!("{count}");
-}
\ No newline at end of file
+pub struct Day8;
+
+impl Solution for Day8 {
+    type Puzzle = Field;
+    type Answer1 = u32;
+    type Answer2 = Unsolved;
+
+    fn parse(input: &str, diagnostics: &mut Diagnostics) -> Result<Field> {
+        read_tree_heights(input, diagnostics)
+    }
+
+    fn part1(field: &Field) -> Result<u32> {
+        calc_tree_visibilities(field)
+            .and_then(visible_tree_count)
+            .ok_or_else(|| Error::validation("The tree field is not rectangular"))
+    }
+
+    fn part2(_: &Field) -> Result<Unsolved> {
+        Err(Error::Unsolved)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        diagnostics::ParseMode,
+        solution::{solve_example, Part},
+    };
+
+    const EXAMPLE: &str = "\
+30373
+25512
+65332
+33549
+35390
+";
+
+    #[test]
+    fn edge_trees_are_visible() {
+        let field = read_tree_heights(EXAMPLE, &mut Diagnostics::default()).unwrap();
+        let trees = calc_tree_visibilities(&field).unwrap();
+
+        assert!(trees[0].visible_left && trees[0].visible_top);
+        assert!(!trees[12].visible_left && !trees[12].visible_right);
+    }
+
+    #[test]
+    fn lenient_mode_skips_rows_of_the_wrong_width() {
+        let mut diagnostics = Diagnostics::new(ParseMode::Lenient);
+        let field = read_tree_heights("123\n45\n678\n", &mut diagnostics).unwrap();
+
+        assert_eq!((field.width, field.height), (3, 2));
+        assert_eq!(diagnostics.warnings.len(), 1);
+    }
+
+    #[test]
+    fn width_comes_from_the_first_valid_row() {
+        let mut diagnostics = Diagnostics::new(ParseMode::Lenient);
+        let field = read_tree_heights("1x\n123\n45\n678\n", &mut diagnostics).unwrap();
+
+        assert_eq!((field.width, field.height), (3, 2));
+        assert_eq!(diagnostics.warnings.len(), 2);
+    }
+
+    #[test]
+    fn rejects_an_empty_first_row() {
+        match read_tree_heights("\n123\n", &mut Diagnostics::default()) {
+            Err(Error::Parse(parse_error)) => {
+                assert_eq!(parse_error.line, 1);
+                assert_eq!(parse_error.message, "Row has no trees");
+            }
+            result => panic!("expected a parse error, got {result:?}"),
+        }
+    }
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(solve_example(&Day8, EXAMPLE, Part::One), "21");
+    }
+
+    #[test]
+    #[ignore = "part 2 is not solved yet"]
+    fn part2_example() {
+        assert_eq!(solve_example(&Day8, EXAMPLE, Part::Two), "8");
+    }
+}
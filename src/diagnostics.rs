@@ -0,0 +1,61 @@
+use crate::error::{Error, Result};
+
+/// How readers treat input they do not expect.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ParseMode {
+    /// Unexpected input is an error.
+    #[default]
+    Strict,
+    /// Unexpected input is skipped and kept as a warning.
+    Lenient,
+}
+
+/// Problems found while reading an input in a given [`ParseMode`].
+#[derive(Debug, Default)]
+pub struct Diagnostics {
+    pub mode: ParseMode,
+    pub warnings: Vec<Error>,
+    /// Most warnings kept, or `None` to keep them all.
+    pub warning_limit: Option<usize>,
+    /// Warnings counted but not kept once past the limit.
+    pub dropped_warnings: usize,
+}
+
+impl Diagnostics {
+    pub fn new(mode: ParseMode) -> Diagnostics {
+        Diagnostics {
+            mode,
+            warnings: Vec::new(),
+            warning_limit: None,
+            dropped_warnings: 0,
+        }
+    }
+
+    /// Keeps only the first `limit` warnings and counts the rest, so reading
+    /// a long input leniently does not hold on to every bad line.
+    pub fn with_warning_limit(mut self, limit: usize) -> Diagnostics {
+        self.warning_limit = Some(limit);
+        self
+    }
+
+    /// Fails with `error` in strict mode. In lenient mode keeps it as a
+    /// warning and returns `Ok`, and the caller skips the offending input.
+    pub fn reject(&mut self, error: Error) -> Result<()> {
+        match self.mode {
+            ParseMode::Strict => Err(error),
+            ParseMode::Lenient => {
+                match self.warning_limit {
+                    Some(limit) if self.warnings.len() >= limit => self.dropped_warnings += 1,
+                    _ => self.warnings.push(error),
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// 1-based char column at which `token`, a subslice of `line`, starts.
+pub fn column_of(line: &str, token: &str) -> usize {
+    let offset = token.as_ptr() as usize - line.as_ptr() as usize;
+    line[..offset].chars().count() + 1
+}
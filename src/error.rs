@@ -0,0 +1,160 @@
+use std::{
+    fmt::{self, Display},
+    io,
+};
+
+use crate::{diagnostics::column_of, input::InputSource};
+
+/// Errors raised while reading, parsing or solving a day's puzzle.
+#[derive(Debug)]
+pub enum Error {
+    /// The input could not be read.
+    Io {
+        source: InputSource,
+        error: io::Error,
+    },
+    /// The input is malformed at a known position.
+    Parse(ParseError),
+    /// The input is well formed but describes an impossible puzzle.
+    Validation(String),
+    /// The requested part has no solution yet.
+    Unsolved,
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// A malformed stretch of input, displayed as the offending line with the
+/// stretch underlined.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub file: Option<InputSource>,
+    /// 1-based line number.
+    pub line: usize,
+    /// 1-based column of the first offending char.
+    pub column: usize,
+    /// Number of offending chars.
+    pub width: usize,
+    /// Full text of the offending line.
+    pub text: String,
+    pub message: String,
+}
+
+impl Error {
+    /// Parse error covering the whole of `text`, the input's 1-based `line`.
+    /// Narrow it down with [`Error::at`].
+    pub fn parse(line: usize, text: &str, message: impl Into<String>) -> Error {
+        Error::Parse(ParseError {
+            file: None,
+            line,
+            column: 1,
+            width: text.chars().count().max(1),
+            text: text.to_string(),
+            message: message.into(),
+        })
+    }
+
+    /// Parse error pointing at `token`, a subslice of `text`, the input's
+    /// 1-based `line`.
+    pub fn parse_token(line: usize, text: &str, token: &str, message: impl Into<String>) -> Error {
+        Error::parse(line, text, message).at(column_of(text, token), token.chars().count())
+    }
+
+    pub fn validation(message: impl Into<String>) -> Error {
+        Error::Validation(message.into())
+    }
+
+    /// Points a parse error at `width` chars starting at 1-based `column`.
+    pub fn at(mut self, column: usize, width: usize) -> Error {
+        if let Error::Parse(parse_error) = &mut self {
+            parse_error.column = column;
+            parse_error.width = width.max(1);
+        }
+        self
+    }
+
+    /// Records the file a parse error was found in.
+    pub fn in_file(mut self, file: &InputSource) -> Error {
+        if let Error::Parse(parse_error) = &mut self {
+            parse_error.file = Some(file.clone());
+        }
+        self
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let file = match &self.file {
+            Some(file) => file.to_string(),
+            None => "<input>".to_string(),
+        };
+        let gutter = " ".repeat(self.line.to_string().len());
+
+        writeln!(f, "{}", self.message)?;
+        writeln!(f, "{gutter}--> {file}:{}:{}", self.line, self.column)?;
+        writeln!(f, "{gutter} |")?;
+        writeln!(f, "{} | {}", self.line, self.text)?;
+        write!(
+            f,
+            "{gutter} | {}{}",
+            " ".repeat(self.column - 1),
+            "^".repeat(self.width)
+        )
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Io { source, error } => write!(f, "could not read {source}: {error}"),
+            Error::Parse(parse_error) => write!(f, "{parse_error}"),
+            Error::Validation(message) => write!(f, "{message}"),
+            Error::Unsolved => write!(f, "not solved yet"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_token_points_at_the_token() {
+        let line = "€ 12 x";
+        let error = Error::parse_token(3, line, &line[4..6], "Bad");
+
+        match error {
+            Error::Parse(parse_error) => {
+                assert_eq!((parse_error.line, parse_error.column), (3, 3));
+                assert_eq!(parse_error.width, 2);
+                assert_eq!(parse_error.text, line);
+            }
+            error => panic!("expected a parse error, got {error:?}"),
+        }
+    }
+
+    #[test]
+    fn parse_errors_underline_the_offending_chars() {
+        let error = Error::parse(12, "1-2,3-x", "Section is not a number").at(7, 1);
+        assert_eq!(
+            error.to_string(),
+            "Section is not a number\n  --> <input>:12:7\n   |\n12 | 1-2,3-x\n   |       ^"
+        );
+
+        let error = Error::parse(7, "move a from 1 to 2", "Expected a number")
+            .at(6, 1)
+            .in_file(&InputSource::File("inputs/day05.txt".into()));
+        assert_eq!(
+            error.to_string(),
+            "Expected a number\n --> inputs/day05.txt:7:6\n  |\n7 | move a from 1 to 2\n  |      ^"
+        );
+    }
+}
@@ -0,0 +1,147 @@
+use std::{
+    env,
+    fmt::{self, Display},
+    fs::{read_to_string, File},
+    io::{self, BufRead, BufReader, Read},
+    path::{Path, PathBuf},
+};
+
+use crate::error::{Error, Result};
+
+/// Environment variable naming a directory that holds `dayNN.txt` inputs.
+pub const INPUTS_DIR_VAR: &str = "AOC_INPUTS_DIR";
+
+/// Directory searched for `dayNN.txt` inputs when no other source is given.
+pub const INPUTS_DIR: &str = "inputs";
+
+/// Answers manifest looked for in the inputs directory by `verify`.
+pub const ANSWERS_FILE: &str = "answers.txt";
+
+/// Where a day's puzzle input is read from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum InputSource {
+    Stdin,
+    File(PathBuf),
+}
+
+impl InputSource {
+    /// Picks the input for `day`, in order of preference: an explicit path
+    /// (`-` meaning stdin), the directory in [`INPUTS_DIR_VAR`], then
+    /// [`INPUTS_DIR`] under the current directory.
+    pub fn resolve(day: u8, explicit: Option<&Path>) -> InputSource {
+        if let Some(path) = explicit {
+            if path == Path::new("-") {
+                return InputSource::Stdin;
+            }
+            return InputSource::File(path.to_path_buf());
+        }
+
+        InputSource::File(inputs_dir().join(input_file_name(day)))
+    }
+
+    pub fn read(&self) -> Result<String> {
+        let input = match self {
+            InputSource::Stdin => {
+                let mut input = String::new();
+                io::stdin().read_to_string(&mut input).map(|_| input)
+            }
+            InputSource::File(path) => read_to_string(path),
+        };
+
+        input.map_err(|error| Error::Io {
+            source: self.clone(),
+            error,
+        })
+    }
+
+    /// Opens the input to be read line by line, without loading all of it.
+    pub fn open(&self) -> Result<impl BufRead> {
+        let reader: Box<dyn BufRead> = match self {
+            InputSource::Stdin => Box::new(io::stdin().lock()),
+            InputSource::File(path) => match File::open(path) {
+                Ok(file) => Box::new(BufReader::new(file)),
+                Err(error) => {
+                    return Err(Error::Io {
+                        source: self.clone(),
+                        error,
+                    })
+                }
+            },
+        };
+        Ok(reader)
+    }
+}
+
+impl Display for InputSource {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InputSource::Stdin => write!(f, "<stdin>"),
+            InputSource::File(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+/// The directory in [`INPUTS_DIR_VAR`], or [`INPUTS_DIR`] if it is unset.
+pub fn inputs_dir() -> PathBuf {
+    match env::var_os(INPUTS_DIR_VAR) {
+        Some(inputs_dir) => PathBuf::from(inputs_dir),
+        None => PathBuf::from(INPUTS_DIR),
+    }
+}
+
+/// Conventional file name of a day's input, e.g. `day07.txt`.
+pub fn input_file_name(day: u8) -> String {
+    format!("day{day:02}.txt")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn explicit_paths_come_first() {
+        assert_eq!(
+            InputSource::resolve(3, Some(Path::new("mine.txt"))),
+            InputSource::File(PathBuf::from("mine.txt"))
+        );
+        assert_eq!(
+            InputSource::resolve(3, Some(Path::new("-"))),
+            InputSource::Stdin
+        );
+    }
+
+    /// Both cases in one test, as tests run in parallel and share the
+    /// environment.
+    #[test]
+    fn defaults_to_the_day_file_in_the_inputs_dir() {
+        let saved = env::var_os(INPUTS_DIR_VAR);
+
+        env::remove_var(INPUTS_DIR_VAR);
+        let unset = InputSource::resolve(3, None);
+        env::set_var(INPUTS_DIR_VAR, "/puzzles");
+        let set = InputSource::resolve(3, None);
+
+        match saved {
+            Some(saved) => env::set_var(INPUTS_DIR_VAR, saved),
+            None => env::remove_var(INPUTS_DIR_VAR),
+        }
+        assert_eq!(unset, InputSource::File(PathBuf::from("inputs/day03.txt")));
+        assert_eq!(set, InputSource::File(PathBuf::from("/puzzles/day03.txt")));
+    }
+
+    #[test]
+    fn opens_files_line_by_line() {
+        let source = InputSource::File(Path::new(env!("CARGO_MANIFEST_DIR")).join("Cargo.toml"));
+        let first_line = source.open().unwrap().lines().next().unwrap().unwrap();
+        assert_eq!(first_line, "[package]");
+
+        let missing = InputSource::File(PathBuf::from("no/such/day99.txt"));
+        assert!(matches!(missing.open(), Err(Error::Io { .. })));
+    }
+
+    #[test]
+    fn input_file_names_pad_the_day() {
+        assert_eq!(input_file_name(7), "day07.txt");
+        assert_eq!(input_file_name(25), "day25.txt");
+    }
+}
@@ -0,0 +1,19 @@
+//! Advent of Code 2022 solutions. Each `dayN` module exposes its input
+//! reader, puzzle types and a `DayN` type implementing [`solution::Solution`].
+
+pub mod bench;
+pub mod day1;
+pub mod day2;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod day7;
+pub mod day8;
+pub mod diagnostics;
+pub mod error;
+pub mod input;
+pub mod solution;
+#[cfg(test)]
+mod test_util;
+pub mod verify;
@@ -1,19 +1,1181 @@
-// mod day1;
-// mod day2;
-// mod day3;
-// mod day4;
-// mod day5;
-// mod day6;
-// mod day7;
-mod day8;
-
-fn main() {
-    // day1::day1();
-    // day2::day2();
-    // day3::day3();
-    // day4::day4();
-    // day5::day5();
-    // day6::day6();
-    // day7::day7();
-    day8::day8();
+use std::{env, iter::Peekable, path::PathBuf, process::ExitCode, time::Duration};
+
+use advent_of_code_2022_rust::{
+    bench::{self, bench_day, DayBench, Summary},
+    day1::{
+        read_elf_calorie_list,
+        stats::{self, distribution},
+        tally_elf_calories, BlankLines,
+    },
+    day2::{
+        read_rpc_guide_with_rules,
+        rules::{read_rules, Rules},
+        tournament::{run_tournament, Entrant, TABLE_HEADER},
+        Strategy,
+    },
+    day3::{
+        groups::{badge_priorities, GROUP_SIZE},
+        packing_errors_priority,
+        priorities::{read_priority_table, PriorityTable},
+        read_rucksacks_contents, Rucksack, SharedBy,
+    },
+    diagnostics::{Diagnostics, ParseMode},
+    error::Error,
+    input::{inputs_dir, InputSource, ANSWERS_FILE, INPUTS_DIR, INPUTS_DIR_VAR},
+    solution::{find_day, Day, Part, Solved, DAYS},
+    verify::{read_answers, verdict, Answers, Tally, Verdict},
+};
+
+const USAGE: &str = "\
+Usage: aoc run <day|all> [--part 1|2] [--input <path|->] [--mode strict|lenient]
+       aoc verify [<day|all>] [--answers <path|->] [--mode strict|lenient]
+       aoc bench [<day|all>] [--iterations <n>] [--json] [--mode strict|lenient]
+       aoc stats [--input <path|->] [--blank-lines collapse|empty] [--buckets <n>] [--json]
+                 [--top <n>] [--mode strict|lenient]
+       aoc rps [--input <path|->] [--rules <path>] [--strategy shapes|outcomes|optimal]
+               [--mode strict|lenient]
+       aoc tournament <guide> <guide>... [--rules <path>]
+                      [--strategy shapes|outcomes|optimal] [--csv] [--mode strict|lenient]
+       aoc rucksacks [--input <path|->] [--priorities <path>] [--compartments <n>]
+                     [--shared any|all] [--group-size <n>] [--repack] [--mode strict|lenient]";
+
+/// Iterations `bench` runs when none are given.
+const DEFAULT_ITERATIONS: usize = 10;
+
+/// Histogram buckets `stats` uses when none are given.
+const DEFAULT_BUCKETS: usize = 10;
+
+/// Warnings `stats --top` keeps while streaming its input.
+const MAX_WARNINGS: usize = 100;
+
+/// Compartments per rucksack `rucksacks` uses when none are given.
+const DEFAULT_COMPARTMENTS: usize = 2;
+
+fn print_usage() {
+    eprintln!("{USAGE}");
+    eprintln!(
+        "Inputs default to ${INPUTS_DIR_VAR}/dayNN.txt, or {INPUTS_DIR}/dayNN.txt if it is unset."
+    );
+    eprintln!("Answers default to {ANSWERS_FILE} in the same directory.");
+}
+
+/// Reads `source` and answers `parts` of `day` from it. Fails if the input
+/// cannot be read or parsed; errors of single parts are left in the answers.
+fn run(
+    day: &Day,
+    parts: &[Part],
+    source: &InputSource,
+    diagnostics: &mut Diagnostics,
+) -> Result<Solved, Error> {
+    let input = source.read()?;
+    let mut solved = day
+        .solver
+        .solve(&input, parts, diagnostics)
+        .map_err(|error| error.in_file(source))?;
+
+    for part_answer in solved.answers.iter_mut() {
+        if let Err(error) = &mut part_answer.answer {
+            *error = std::mem::replace(error, Error::Unsolved).in_file(source);
+        }
+    }
+
+    Ok(solved)
+}
+
+fn report(day: u8, error: &Error) {
+    eprintln!("error: day {day}: {error}");
+}
+
+fn report_warnings(day: u8, source: &InputSource, diagnostics: Diagnostics) {
+    for warning in diagnostics.warnings {
+        eprintln!("warning: day {day}: {}", warning.in_file(source));
+    }
+    if diagnostics.dropped_warnings > 0 {
+        eprintln!(
+            "warning: day {day}: {} more warnings not shown",
+            diagnostics.dropped_warnings
+        );
+    }
+}
+
+fn format_duration(duration: Duration) -> String {
+    format!("{:.3} ms", duration.as_secs_f64() * 1000.0)
+}
+
+#[derive(Debug)]
+enum DaySelection {
+    All,
+    Day(u8),
+}
+
+impl DaySelection {
+    fn days(&self) -> Vec<&'static Day> {
+        match self {
+            DaySelection::All => DAYS.iter().collect(),
+            DaySelection::Day(number) => vec![find_day(*number).unwrap()],
+        }
+    }
+}
+
+#[derive(Debug)]
+struct RunArgs {
+    days: DaySelection,
+    part: Option<Part>,
+    input: Option<PathBuf>,
+    mode: ParseMode,
+}
+
+#[derive(Debug)]
+struct VerifyArgs {
+    days: DaySelection,
+    answers: Option<PathBuf>,
+    mode: ParseMode,
+}
+
+#[derive(Debug)]
+struct BenchArgs {
+    days: DaySelection,
+    iterations: usize,
+    json: bool,
+    mode: ParseMode,
+}
+
+#[derive(Debug)]
+struct StatsArgs {
+    input: Option<PathBuf>,
+    blank_lines: BlankLines,
+    buckets: usize,
+    json: bool,
+    /// Only rank the elves carrying the most calories, streaming the input.
+    top: Option<usize>,
+    mode: ParseMode,
+}
+
+#[derive(Debug)]
+struct RpsArgs {
+    input: Option<PathBuf>,
+    rules: Option<PathBuf>,
+    strategy: Strategy,
+    mode: ParseMode,
+}
+
+#[derive(Debug)]
+struct TournamentArgs {
+    guides: Vec<PathBuf>,
+    rules: Option<PathBuf>,
+    strategy: Strategy,
+    csv: bool,
+    mode: ParseMode,
+}
+
+#[derive(Debug)]
+struct RucksacksArgs {
+    input: Option<PathBuf>,
+    priorities: Option<PathBuf>,
+    compartments: usize,
+    shared_by: SharedBy,
+    group_size: usize,
+    repack: bool,
+    mode: ParseMode,
+}
+
+#[derive(Debug)]
+enum Command {
+    Run(RunArgs),
+    Verify(VerifyArgs),
+    Bench(BenchArgs),
+    Stats(StatsArgs),
+    Rps(RpsArgs),
+    Tournament(TournamentArgs),
+    Rucksacks(RucksacksArgs),
+}
+
+fn parse_day_selection(day: &str) -> Result<DaySelection, String> {
+    if day == "all" {
+        return Ok(DaySelection::All);
+    }
+    match day.parse::<u8>() {
+        Ok(day) if find_day(day).is_some() => Ok(DaySelection::Day(day)),
+        _ => Err(format!("Unknown day \"{day}\".")),
+    }
+}
+
+fn parse_mode(mode: Option<String>) -> Result<ParseMode, String> {
+    match mode.as_deref() {
+        Some("strict") => Ok(ParseMode::Strict),
+        Some("lenient") => Ok(ParseMode::Lenient),
+        Some(mode) => Err(format!("Unknown mode \"{mode}\".")),
+        None => Err("Missing value for --mode.".to_string()),
+    }
+}
+
+fn parse_path(flag: &str, path: Option<String>) -> Result<PathBuf, String> {
+    path.map(PathBuf::from)
+        .ok_or_else(|| format!("Missing value for {flag}."))
+}
+
+fn parse_run_args(mut args: impl Iterator<Item = String>) -> Result<RunArgs, String> {
+    let days = match args.next() {
+        Some(day) => parse_day_selection(&day)?,
+        None => return Err("Missing day to run.".to_string()),
+    };
+
+    let mut run_args = RunArgs {
+        days,
+        part: None,
+        input: None,
+        mode: ParseMode::default(),
+    };
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--part" => {
+                run_args.part = match args.next().as_deref() {
+                    Some("1") => Some(Part::One),
+                    Some("2") => Some(Part::Two),
+                    Some(part) => return Err(format!("Unknown part \"{part}\".")),
+                    None => return Err("Missing value for --part.".to_string()),
+                }
+            }
+            "--input" => run_args.input = Some(parse_path("--input", args.next())?),
+            "--mode" => run_args.mode = parse_mode(args.next())?,
+            arg => return Err(format!("Unknown argument \"{arg}\".")),
+        }
+    }
+
+    if let (DaySelection::All, Some(_)) = (&run_args.days, &run_args.input) {
+        return Err("--input can only be used when running a single day.".to_string());
+    }
+
+    Ok(run_args)
+}
+
+/// Takes the optional day selection leading a command's arguments.
+fn parse_optional_day_selection(
+    args: &mut Peekable<impl Iterator<Item = String>>,
+) -> Result<DaySelection, String> {
+    match args.next_if(|arg| !arg.starts_with("--")) {
+        Some(day) => parse_day_selection(&day),
+        None => Ok(DaySelection::All),
+    }
+}
+
+fn parse_verify_args(args: impl Iterator<Item = String>) -> Result<VerifyArgs, String> {
+    let mut args = args.peekable();
+    let days = parse_optional_day_selection(&mut args)?;
+
+    let mut verify_args = VerifyArgs {
+        days,
+        answers: None,
+        mode: ParseMode::default(),
+    };
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--answers" => verify_args.answers = Some(parse_path("--answers", args.next())?),
+            "--mode" => verify_args.mode = parse_mode(args.next())?,
+            arg => return Err(format!("Unknown argument \"{arg}\".")),
+        }
+    }
+
+    Ok(verify_args)
+}
+
+fn parse_bench_args(args: impl Iterator<Item = String>) -> Result<BenchArgs, String> {
+    let mut args = args.peekable();
+    let days = parse_optional_day_selection(&mut args)?;
+
+    let mut bench_args = BenchArgs {
+        days,
+        iterations: DEFAULT_ITERATIONS,
+        json: false,
+        mode: ParseMode::default(),
+    };
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--iterations" => bench_args.iterations = parse_count("--iterations", args.next())?,
+            "--json" => bench_args.json = true,
+            "--mode" => bench_args.mode = parse_mode(args.next())?,
+            arg => return Err(format!("Unknown argument \"{arg}\".")),
+        }
+    }
+
+    Ok(bench_args)
+}
+
+/// Parses the value of a count flag, which must be at least 1.
+fn parse_count(flag: &str, count: Option<String>) -> Result<usize, String> {
+    match count {
+        Some(count) => match count.parse::<usize>() {
+            Ok(count) if count > 0 => Ok(count),
+            _ => Err(format!("Invalid value \"{count}\" for {flag}.")),
+        },
+        None => Err(format!("Missing value for {flag}.")),
+    }
+}
+
+fn parse_stats_args(mut args: impl Iterator<Item = String>) -> Result<StatsArgs, String> {
+    let mut stats_args = StatsArgs {
+        input: None,
+        blank_lines: BlankLines::default(),
+        buckets: DEFAULT_BUCKETS,
+        json: false,
+        top: None,
+        mode: ParseMode::default(),
+    };
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--input" => stats_args.input = Some(parse_path("--input", args.next())?),
+            "--blank-lines" => {
+                stats_args.blank_lines = match args.next().as_deref() {
+                    Some("collapse") => BlankLines::Collapse,
+                    Some("empty") => BlankLines::EmptyElves,
+                    Some(blank_lines) => {
+                        return Err(format!("Unknown blank line grouping \"{blank_lines}\"."))
+                    }
+                    None => return Err("Missing value for --blank-lines.".to_string()),
+                }
+            }
+            "--buckets" => stats_args.buckets = parse_count("--buckets", args.next())?,
+            "--json" => stats_args.json = true,
+            "--top" => stats_args.top = Some(parse_count("--top", args.next())?),
+            "--mode" => stats_args.mode = parse_mode(args.next())?,
+            arg => return Err(format!("Unknown argument \"{arg}\".")),
+        }
+    }
+
+    if stats_args.top.is_some() && stats_args.json {
+        return Err("--top cannot be combined with --json.".to_string());
+    }
+
+    Ok(stats_args)
+}
+
+fn parse_strategy(strategy: Option<String>) -> Result<Strategy, String> {
+    match strategy.as_deref() {
+        Some("shapes") => Ok(Strategy::Shapes),
+        Some("outcomes") => Ok(Strategy::Outcomes),
+        Some("optimal") => Ok(Strategy::Optimal),
+        Some(strategy) => Err(format!("Unknown strategy \"{strategy}\".")),
+        None => Err("Missing value for --strategy.".to_string()),
+    }
+}
+
+fn parse_rps_args(mut args: impl Iterator<Item = String>) -> Result<RpsArgs, String> {
+    let mut rps_args = RpsArgs {
+        input: None,
+        rules: None,
+        strategy: Strategy::default(),
+        mode: ParseMode::default(),
+    };
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--input" => rps_args.input = Some(parse_path("--input", args.next())?),
+            "--rules" => rps_args.rules = Some(parse_path("--rules", args.next())?),
+            "--strategy" => rps_args.strategy = parse_strategy(args.next())?,
+            "--mode" => rps_args.mode = parse_mode(args.next())?,
+            arg => return Err(format!("Unknown argument \"{arg}\".")),
+        }
+    }
+
+    Ok(rps_args)
+}
+
+fn parse_tournament_args(mut args: impl Iterator<Item = String>) -> Result<TournamentArgs, String> {
+    let mut tournament_args = TournamentArgs {
+        guides: Vec::new(),
+        rules: None,
+        strategy: Strategy::default(),
+        csv: false,
+        mode: ParseMode::default(),
+    };
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--rules" => tournament_args.rules = Some(parse_path("--rules", args.next())?),
+            "--strategy" => tournament_args.strategy = parse_strategy(args.next())?,
+            "--csv" => tournament_args.csv = true,
+            "--mode" => tournament_args.mode = parse_mode(args.next())?,
+            arg if arg.starts_with("--") => return Err(format!("Unknown argument \"{arg}\".")),
+            guide => tournament_args.guides.push(PathBuf::from(guide)),
+        }
+    }
+
+    if tournament_args.guides.len() < 2 {
+        return Err("A tournament needs at least two guides.".to_string());
+    }
+
+    Ok(tournament_args)
+}
+
+fn parse_rucksacks_args(mut args: impl Iterator<Item = String>) -> Result<RucksacksArgs, String> {
+    let mut rucksacks_args = RucksacksArgs {
+        input: None,
+        priorities: None,
+        compartments: DEFAULT_COMPARTMENTS,
+        shared_by: SharedBy::default(),
+        group_size: GROUP_SIZE,
+        repack: false,
+        mode: ParseMode::default(),
+    };
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--input" => rucksacks_args.input = Some(parse_path("--input", args.next())?),
+            "--priorities" => {
+                rucksacks_args.priorities = Some(parse_path("--priorities", args.next())?)
+            }
+            "--compartments" => {
+                rucksacks_args.compartments = parse_count("--compartments", args.next())?
+            }
+            "--shared" => {
+                rucksacks_args.shared_by = match args.next().as_deref() {
+                    Some("any") => SharedBy::AtLeastTwo,
+                    Some("all") => SharedBy::All,
+                    Some(shared_by) => return Err(format!("Unknown sharing \"{shared_by}\".")),
+                    None => return Err("Missing value for --shared.".to_string()),
+                }
+            }
+            "--group-size" => rucksacks_args.group_size = parse_count("--group-size", args.next())?,
+            "--repack" => rucksacks_args.repack = true,
+            "--mode" => rucksacks_args.mode = parse_mode(args.next())?,
+            arg => return Err(format!("Unknown argument \"{arg}\".")),
+        }
+    }
+
+    Ok(rucksacks_args)
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Command, String> {
+    match args.next().as_deref() {
+        None => Ok(Command::Run(RunArgs {
+            days: DaySelection::All,
+            part: None,
+            input: None,
+            mode: ParseMode::default(),
+        })),
+        Some("run") => parse_run_args(args).map(Command::Run),
+        Some("verify") => parse_verify_args(args).map(Command::Verify),
+        Some("bench") => parse_bench_args(args).map(Command::Bench),
+        Some("stats") => parse_stats_args(args).map(Command::Stats),
+        Some("rps") => parse_rps_args(args).map(Command::Rps),
+        Some("tournament") => parse_tournament_args(args).map(Command::Tournament),
+        Some("rucksacks") => parse_rucksacks_args(args).map(Command::Rucksacks),
+        Some(command) => Err(format!("Unknown command \"{command}\".")),
+    }
+}
+
+/// Prints `rows` under `header` in aligned columns, the first one right
+/// aligned as it holds day numbers.
+fn print_table(header: &[&str], rows: &[Vec<String>]) {
+    let mut widths: Vec<usize> = header.iter().map(|cell| cell.len()).collect();
+    for row in rows.iter() {
+        for (width, cell) in widths.iter_mut().zip(row.iter()) {
+            *width = (*width).max(cell.len());
+        }
+    }
+
+    let print_row = |row: &[String]| {
+        let last = row.len() - 1;
+        let cells: Vec<String> = row
+            .iter()
+            .enumerate()
+            .map(|(index, cell)| match index {
+                0 => format!("{cell:>0$}", widths[0]),
+                index if index == last => cell.to_string(),
+                index => format!("{cell:<0$}", widths[index]),
+            })
+            .collect();
+        println!("{}", cells.join(" | ").trim_end());
+    };
+
+    print_row(
+        &header
+            .iter()
+            .map(|cell| cell.to_string())
+            .collect::<Vec<_>>(),
+    );
+    println!(
+        "{}",
+        widths
+            .iter()
+            .map(|width| "-".repeat(*width))
+            .collect::<Vec<_>>()
+            .join("-+-")
+    );
+    rows.iter().for_each(|row| print_row(row));
+}
+
+fn column(part: Part) -> usize {
+    match part {
+        Part::One => 1,
+        Part::Two => 2,
+    }
+}
+
+fn run_command(run_args: RunArgs) -> ExitCode {
+    let parts = match run_args.part {
+        Some(part) => vec![part],
+        None => Part::ALL.to_vec(),
+    };
+
+    match run_args.days {
+        DaySelection::Day(number) => {
+            let day = find_day(number).unwrap();
+            let source = InputSource::resolve(number, run_args.input.as_deref());
+            let mut diagnostics = Diagnostics::new(run_args.mode);
+
+            let result = run(day, &parts, &source, &mut diagnostics);
+            report_warnings(number, &source, diagnostics);
+
+            let solved = match result {
+                Ok(solved) => solved,
+                Err(error) => {
+                    report(number, &error);
+                    return ExitCode::FAILURE;
+                }
+            };
+
+            let mut failed = false;
+            for part_answer in solved.answers {
+                let part = part_answer.part;
+                match part_answer.answer {
+                    Ok(answer) => println!("Day {number} part {part}: {answer}"),
+                    Err(Error::Unsolved) => println!("Day {number} part {part}: not solved"),
+                    Err(error) => {
+                        report(number, &error);
+                        failed = true;
+                    }
+                }
+            }
+
+            if failed {
+                return ExitCode::FAILURE;
+            }
+        }
+        DaySelection::All => {
+            let mut errors: Vec<(u8, Error)> = Vec::new();
+            let rows: Vec<Vec<String>> = DAYS
+                .iter()
+                .map(|day| {
+                    let mut row = vec![day.number.to_string(), "-".to_string(), "-".to_string()];
+                    let source = InputSource::resolve(day.number, None);
+                    let mut diagnostics = Diagnostics::new(run_args.mode);
+
+                    let result = run(day, &parts, &source, &mut diagnostics);
+                    report_warnings(day.number, &source, diagnostics);
+
+                    let solved = match result {
+                        Ok(solved) => solved,
+                        Err(error) => {
+                            row[1] = "error".to_string();
+                            errors.push((day.number, error));
+                            return row;
+                        }
+                    };
+                    for part_answer in solved.answers {
+                        let column = column(part_answer.part);
+                        match part_answer.answer {
+                            Ok(answer) => row[column] = answer,
+                            Err(Error::Unsolved) => (),
+                            Err(error) => {
+                                row[column] = "error".to_string();
+                                errors.push((day.number, error));
+                            }
+                        }
+                    }
+                    row
+                })
+                .collect();
+
+            print_table(&["Day", "Part 1", "Part 2"], &rows);
+
+            if !errors.is_empty() {
+                errors.iter().for_each(|(day, error)| report(*day, error));
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    ExitCode::SUCCESS
+}
+
+fn load_answers(source: &InputSource) -> Result<Answers, Error> {
+    read_answers(&source.read()?).map_err(|error| error.in_file(source))
+}
+
+fn verify_command(verify_args: VerifyArgs) -> ExitCode {
+    let answers_path = verify_args
+        .answers
+        .unwrap_or_else(|| inputs_dir().join(ANSWERS_FILE));
+    let answers_source = match answers_path.to_str() {
+        Some("-") => InputSource::Stdin,
+        _ => InputSource::File(answers_path),
+    };
+    let answers = match load_answers(&answers_source) {
+        Ok(answers) => answers,
+        Err(error) => {
+            eprintln!("error: {error}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut errors: Vec<(u8, Error)> = Vec::new();
+    let mut rows: Vec<Vec<String>> = Vec::new();
+    let mut tally = Tally::default();
+
+    for day in verify_args.days.days() {
+        let source = InputSource::resolve(day.number, None);
+        let mut diagnostics = Diagnostics::new(verify_args.mode);
+
+        let result = run(day, &Part::ALL, &source, &mut diagnostics);
+        report_warnings(day.number, &source, diagnostics);
+
+        // Parts of a day whose input failed have no answer.
+        let part_answers = match result {
+            Ok(solved) => solved
+                .answers
+                .into_iter()
+                .map(|part_answer| {
+                    (
+                        part_answer.part,
+                        Some(part_answer.answer),
+                        part_answer.elapsed,
+                    )
+                })
+                .collect::<Vec<_>>(),
+            Err(error) => {
+                errors.push((day.number, error));
+                Part::ALL
+                    .iter()
+                    .map(|&part| (part, None, Duration::ZERO))
+                    .collect()
+            }
+        };
+
+        for (part, answer, elapsed) in part_answers {
+            let (actual, answer) = match answer {
+                Some(Ok(answer)) => (Some(answer.clone()), answer),
+                Some(Err(Error::Unsolved)) => (None, "not solved".to_string()),
+                Some(Err(error)) => {
+                    errors.push((day.number, error));
+                    (None, "error".to_string())
+                }
+                None => (None, "error".to_string()),
+            };
+
+            let expected = answers.expected(day.number, part);
+            let verdict = verdict(expected, actual.as_deref());
+            tally.record(verdict);
+            let details = match (verdict, expected) {
+                (Verdict::Fail, Some(expected)) => format!("{answer} (expected {expected})"),
+                _ => answer,
+            };
+
+            rows.push(vec![
+                day.number.to_string(),
+                part.to_string(),
+                verdict.label().to_string(),
+                format_duration(elapsed),
+                details,
+            ]);
+        }
+    }
+
+    print_table(&["Day", "Part", "Result", "Time", "Answer"], &rows);
+    println!("{tally}");
+    errors.iter().for_each(|(day, error)| report(*day, error));
+
+    if !tally.succeeded(errors.len()) {
+        return ExitCode::FAILURE;
+    }
+
+    ExitCode::SUCCESS
+}
+
+fn format_summary(summary: Option<Summary>) -> String {
+    match summary {
+        Some(summary) => format!(
+            "{} / {} / {}",
+            format_duration(summary.min),
+            format_duration(summary.median),
+            format_duration(summary.max)
+        ),
+        None => "-".to_string(),
+    }
+}
+
+fn bench_command(bench_args: BenchArgs) -> ExitCode {
+    let mut errors: Vec<(u8, Error)> = Vec::new();
+    let mut benches: Vec<DayBench> = Vec::new();
+
+    for day in bench_args.days.days() {
+        let source = InputSource::resolve(day.number, None);
+        let mut diagnostics = Diagnostics::new(bench_args.mode);
+
+        let result = source.read().and_then(|input| {
+            bench_day(day, &input, bench_args.iterations, &mut diagnostics)
+                .map_err(|error| error.in_file(&source))
+        });
+        report_warnings(day.number, &source, diagnostics);
+
+        match result {
+            Ok(bench) => benches.push(bench),
+            Err(error) => errors.push((day.number, error)),
+        }
+    }
+
+    if bench_args.json {
+        println!("{}", bench::to_json(&benches));
+    } else {
+        println!(
+            "min / median / max over {} iterations",
+            bench_args.iterations
+        );
+        let rows: Vec<Vec<String>> = benches
+            .iter()
+            .map(|bench| {
+                vec![
+                    bench.day.to_string(),
+                    format_summary(Some(bench.parse)),
+                    format_summary(bench.part1),
+                    format_summary(bench.part2),
+                ]
+            })
+            .collect();
+        print_table(&["Day", "Parse", "Part 1", "Part 2"], &rows);
+    }
+
+    if !errors.is_empty() {
+        errors.iter().for_each(|(day, error)| report(*day, error));
+        return ExitCode::FAILURE;
+    }
+
+    ExitCode::SUCCESS
+}
+
+fn format_option<T: ToString>(value: Option<T>) -> String {
+    value.map_or_else(|| "-".to_string(), |value| value.to_string())
+}
+
+/// Ranks the `top_n` elves carrying the most calories, reading the input
+/// line by line.
+fn top_elves_command(source: InputSource, stats_args: StatsArgs, top_n: usize) -> ExitCode {
+    let mut diagnostics = Diagnostics::new(stats_args.mode).with_warning_limit(MAX_WARNINGS);
+
+    let result = source.open().and_then(|reader| {
+        tally_elf_calories(
+            reader,
+            &source,
+            stats_args.blank_lines,
+            top_n,
+            &mut diagnostics,
+        )
+        .map_err(|error| error.in_file(&source))
+    });
+    report_warnings(1, &source, diagnostics);
+
+    let tally = match result {
+        Ok(tally) => tally,
+        Err(error) => {
+            report(1, &error);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let rows: Vec<Vec<String>> = tally
+        .top
+        .iter()
+        .enumerate()
+        .map(|(index, elf)| {
+            vec![
+                (index + 1).to_string(),
+                elf.id.to_string(),
+                elf.total_calories.to_string(),
+            ]
+        })
+        .collect();
+    print_table(&["Rank", "Elf", "Total"], &rows);
+    println!("{} elves", tally.elf_count);
+
+    ExitCode::SUCCESS
+}
+
+fn stats_command(stats_args: StatsArgs) -> ExitCode {
+    let source = InputSource::resolve(1, stats_args.input.as_deref());
+    if let Some(top_n) = stats_args.top {
+        return top_elves_command(source, stats_args, top_n);
+    }
+    let mut diagnostics = Diagnostics::new(stats_args.mode);
+
+    let result = source.read().and_then(|input| {
+        read_elf_calorie_list(&input, stats_args.blank_lines, &mut diagnostics)
+            .map_err(|error| error.in_file(&source))
+    });
+    report_warnings(1, &source, diagnostics);
+
+    let elves = match result {
+        Ok(elves) => elves,
+        Err(error) => {
+            report(1, &error);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let stats = elves
+        .iter()
+        .map(|elf| elf.stats())
+        .collect::<Result<Vec<_>, Error>>()
+        .and_then(|elf_stats| Ok((elf_stats, distribution(&elves, stats_args.buckets)?)));
+    let (elf_stats, distribution) = match stats {
+        Ok(stats) => stats,
+        Err(error) => {
+            report(1, &error);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if stats_args.json {
+        println!("{}", stats::to_json(&elf_stats, distribution.as_ref()));
+        return ExitCode::SUCCESS;
+    }
+
+    let rows: Vec<Vec<String>> = elf_stats
+        .iter()
+        .map(|stats| {
+            vec![
+                stats.elf_id.to_string(),
+                stats.item_count.to_string(),
+                stats.total_calories.to_string(),
+                format_option(stats.min),
+                format_option(stats.max),
+                format_option(stats.mean.map(|mean| format!("{mean:.1}"))),
+                format_option(stats.median.map(|median| format!("{median:.1}"))),
+            ]
+        })
+        .collect();
+    print_table(
+        &["Elf", "Items", "Total", "Min", "Max", "Mean", "Median"],
+        &rows,
+    );
+
+    if let Some(distribution) = distribution {
+        println!();
+        let rows: Vec<Vec<String>> = distribution
+            .histogram
+            .iter()
+            .map(|bucket| {
+                vec![
+                    format!("{}-{}", bucket.start, bucket.end),
+                    bucket.elf_count.to_string(),
+                    "#".repeat(bucket.elf_count),
+                ]
+            })
+            .collect();
+        print_table(&["Total", "Elves", "Histogram"], &rows);
+
+        println!();
+        let rows: Vec<Vec<String>> = distribution
+            .percentiles
+            .iter()
+            .map(|(percentile, total)| vec![format!("p{percentile}"), total.to_string()])
+            .collect();
+        print_table(&["Percentile", "Total"], &rows);
+    }
+
+    ExitCode::SUCCESS
+}
+
+fn load_rules(path: Option<PathBuf>) -> Result<Rules, Error> {
+    match path {
+        Some(path) => {
+            let source = InputSource::File(path);
+            read_rules(&source.read()?).map_err(|error| error.in_file(&source))
+        }
+        None => Ok(Rules::default()),
+    }
+}
+
+fn rps_command(rps_args: RpsArgs) -> ExitCode {
+    let rules = match load_rules(rps_args.rules) {
+        Ok(rules) => rules,
+        Err(error) => {
+            report(2, &error);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let source = InputSource::resolve(2, rps_args.input.as_deref());
+    let mut diagnostics = Diagnostics::new(rps_args.mode);
+
+    let result = source.read().and_then(|input| {
+        read_rpc_guide_with_rules(&input, rules, &mut diagnostics)
+            .map_err(|error| error.in_file(&source))
+    });
+    report_warnings(2, &source, diagnostics);
+
+    let scored = result.and_then(|rpc| {
+        let rounds = rpc.rounds(rps_args.strategy)?;
+        let scores = rpc.calculate_scores(rps_args.strategy)?;
+        Ok((rpc, rounds, scores))
+    });
+    let (rpc, rounds, scores) = match scored {
+        Ok(scored) => scored,
+        Err(error) => {
+            report(2, &error);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let rows: Vec<Vec<String>> = rounds
+        .iter()
+        .zip(scores.rounds.iter())
+        .enumerate()
+        .map(|(index, (round, score))| {
+            vec![
+                (index + 1).to_string(),
+                rpc.rules.name(round.p1).to_string(),
+                rpc.rules.name(round.p2).to_string(),
+                format!("{} + {}", score.p1_shape, score.p1_outcome),
+                format!("{} + {}", score.p2_shape, score.p2_outcome),
+            ]
+        })
+        .collect();
+    print_table(
+        &[
+            "Round",
+            "Opponent",
+            "Player",
+            "Opponent score",
+            "Player score",
+        ],
+        &rows,
+    );
+    println!(
+        "Opponent total: {}, player total: {}",
+        scores.p1_total, scores.p2_total
+    );
+
+    let analysis = rpc.analyse();
+    println!(
+        "Best possible total: {}, random reply: {} at worst, {:.2} expected",
+        analysis.best_total, analysis.worst_total, analysis.expected_total
+    );
+
+    ExitCode::SUCCESS
+}
+
+/// Reads the guide at `path` and the shapes it plays with `strategy`.
+/// Names guides by their file stems, or by their full paths where two
+/// stems are the same.
+fn entrant_names(paths: &[PathBuf]) -> Vec<String> {
+    let stems: Vec<Option<String>> = paths
+        .iter()
+        .map(|path| {
+            path.file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+        })
+        .collect();
+
+    paths
+        .iter()
+        .zip(&stems)
+        .map(|(path, stem)| match stem {
+            Some(stem)
+                if stems
+                    .iter()
+                    .flatten()
+                    .filter(|&other| other == stem)
+                    .count()
+                    == 1 =>
+            {
+                stem.clone()
+            }
+            _ => path.display().to_string(),
+        })
+        .collect()
+}
+
+fn load_entrant(
+    name: String,
+    path: PathBuf,
+    rules: &Rules,
+    strategy: Strategy,
+    mode: ParseMode,
+) -> Result<Entrant, Error> {
+    let source = InputSource::File(path);
+    let mut diagnostics = Diagnostics::new(mode);
+
+    let result = source.read().and_then(|input| {
+        read_rpc_guide_with_rules(&input, rules.clone(), &mut diagnostics)
+            .and_then(|rpc| Entrant::from_guide(name, &rpc, strategy))
+            .map_err(|error| error.in_file(&source))
+    });
+    report_warnings(2, &source, diagnostics);
+
+    result
+}
+
+fn tournament_command(tournament_args: TournamentArgs) -> ExitCode {
+    let strategy = tournament_args.strategy;
+    let mode = tournament_args.mode;
+    let tournament = load_rules(tournament_args.rules).and_then(|rules| {
+        let names = entrant_names(&tournament_args.guides);
+        let entrants = names
+            .into_iter()
+            .zip(tournament_args.guides)
+            .map(|(name, path)| load_entrant(name, path, &rules, strategy, mode))
+            .collect::<Result<Vec<_>, Error>>()?;
+        run_tournament(&rules, &entrants)
+    });
+    let tournament = match tournament {
+        Ok(tournament) => tournament,
+        Err(error) => {
+            report(2, &error);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if tournament_args.csv {
+        println!("{}", tournament.to_csv());
+        return ExitCode::SUCCESS;
+    }
+
+    print_table(&TABLE_HEADER, &tournament.table_rows());
+    println!();
+    let mut header = vec!["Guide"];
+    header.extend(tournament.names.iter().map(String::as_str));
+    print_table(&header, &tournament.matrix_rows());
+
+    ExitCode::SUCCESS
+}
+
+fn load_priority_table(path: Option<PathBuf>) -> Result<PriorityTable, Error> {
+    match path {
+        Some(path) => {
+            let source = InputSource::File(path);
+            read_priority_table(&source.read()?).map_err(|error| error.in_file(&source))
+        }
+        None => Ok(PriorityTable::default()),
+    }
+}
+
+/// Prints the moves repacking every rucksack, and how many there are in all.
+fn print_repackings(rucksacks: &[Rucksack]) {
+    let (mut moves, mut moved_items, mut stuck) = (0, 0, 0);
+    let rows: Vec<Vec<String>> = rucksacks
+        .iter()
+        .enumerate()
+        .map(|(index, rucksack)| {
+            let details = match rucksack.repacking() {
+                Ok(repacking) if repacking.moves.is_empty() => "-".to_string(),
+                Ok(repacking) => {
+                    moves += repacking.moves.len();
+                    moved_items += repacking.moved_items();
+                    repacking
+                        .moves
+                        .iter()
+                        .map(|item_move| {
+                            format!(
+                                "{} {} {}->{}",
+                                item_move.count,
+                                item_move.item,
+                                item_move.from + 1,
+                                item_move.to + 1
+                            )
+                        })
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                }
+                Err(error) => {
+                    stuck += 1;
+                    error.to_string()
+                }
+            };
+            vec![(index + 1).to_string(), details]
+        })
+        .collect();
+
+    print_table(&["Rucksack", "Moves"], &rows);
+    println!("{moves} moves, {moved_items} items moved, {stuck} rucksacks cannot be repacked");
+}
+
+fn rucksacks_command(rucksacks_args: RucksacksArgs) -> ExitCode {
+    let table = match load_priority_table(rucksacks_args.priorities) {
+        Ok(table) => table,
+        Err(error) => {
+            report(3, &error);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let source = InputSource::resolve(3, rucksacks_args.input.as_deref());
+    let mut diagnostics = Diagnostics::new(rucksacks_args.mode);
+
+    let result = source.read().and_then(|input| {
+        read_rucksacks_contents(
+            &input,
+            rucksacks_args.compartments,
+            &table,
+            &mut diagnostics,
+        )
+        .map_err(|error| error.in_file(&source))
+    });
+    report_warnings(3, &source, diagnostics);
+
+    let rucksacks = match result {
+        Ok(rucksacks) => rucksacks,
+        Err(error) => {
+            report(3, &error);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match packing_errors_priority(&table, &rucksacks, rucksacks_args.shared_by) {
+        Ok(packing_errors) => println!("Packing errors: {packing_errors}"),
+        Err(error) => {
+            report(3, &error);
+            return ExitCode::FAILURE;
+        }
+    }
+
+    let badges = badge_priorities(&table, &rucksacks, rucksacks_args.group_size);
+    match &badges {
+        Ok(badges) => println!("Badges: {badges}"),
+        Err(error) => report(3, error),
+    }
+
+    if rucksacks_args.repack {
+        println!();
+        print_repackings(&rucksacks);
+    }
+
+    match badges {
+        Ok(_) => ExitCode::SUCCESS,
+        Err(_) => ExitCode::FAILURE,
+    }
+}
+
+fn main() -> ExitCode {
+    let command = match parse_args(env::args().skip(1)) {
+        Ok(command) => command,
+        Err(message) => {
+            eprintln!("{message}");
+            print_usage();
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match command {
+        Command::Run(run_args) => run_command(run_args),
+        Command::Verify(verify_args) => verify_command(verify_args),
+        Command::Bench(bench_args) => bench_command(bench_args),
+        Command::Stats(stats_args) => stats_command(stats_args),
+        Command::Rps(rps_args) => rps_command(rps_args),
+        Command::Tournament(tournament_args) => tournament_command(tournament_args),
+        Command::Rucksacks(rucksacks_args) => rucksacks_command(rucksacks_args),
+    }
 }
@@ -0,0 +1,153 @@
+use std::{
+    fmt::{self, Display},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    day1, day2, day3, day4, day5, day6, day7, day8, diagnostics::Diagnostics, error::Result,
+};
+
+/// A day's puzzle: its input is parsed once and both parts are answered from
+/// the parsed value.
+pub trait Solution {
+    type Puzzle;
+    type Answer1: Display;
+    type Answer2: Display;
+
+    fn parse(input: &str, diagnostics: &mut Diagnostics) -> Result<Self::Puzzle>;
+    fn part1(puzzle: &Self::Puzzle) -> Result<Self::Answer1>;
+    fn part2(puzzle: &Self::Puzzle) -> Result<Self::Answer2>;
+}
+
+/// Answer type of a part that has not been solved yet, whose solver returns
+/// [`Error::Unsolved`](crate::error::Error::Unsolved).
+pub enum Unsolved {}
+
+impl Display for Unsolved {
+    fn fmt(&self, _: &mut fmt::Formatter) -> fmt::Result {
+        match *self {}
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const ALL: [Part; 2] = [Part::One, Part::Two];
+}
+
+impl Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Part::One => write!(f, "1"),
+            Part::Two => write!(f, "2"),
+        }
+    }
+}
+
+/// A part's answer and how long it took to work out.
+pub struct PartAnswer {
+    pub part: Part,
+    pub answer: Result<String>,
+    pub elapsed: Duration,
+}
+
+/// What a [`Solver`] worked out from one input.
+pub struct Solved {
+    pub parse_elapsed: Duration,
+    pub answers: Vec<PartAnswer>,
+}
+
+/// Object safe view of a [`Solution`] so days can be stored side by side.
+pub trait Solver {
+    /// Parses `input` once and answers each of `parts` from it, timing every
+    /// step.
+    fn solve(&self, input: &str, parts: &[Part], diagnostics: &mut Diagnostics) -> Result<Solved>;
+}
+
+impl<S: Solution> Solver for S {
+    fn solve(&self, input: &str, parts: &[Part], diagnostics: &mut Diagnostics) -> Result<Solved> {
+        let start = Instant::now();
+        let puzzle = S::parse(input, diagnostics)?;
+        let parse_elapsed = start.elapsed();
+
+        let answers = parts
+            .iter()
+            .map(|&part| {
+                let start = Instant::now();
+                let answer = match part {
+                    Part::One => S::part1(&puzzle).map(|answer| answer.to_string()),
+                    Part::Two => S::part2(&puzzle).map(|answer| answer.to_string()),
+                };
+                PartAnswer {
+                    part,
+                    answer,
+                    elapsed: start.elapsed(),
+                }
+            })
+            .collect();
+
+        Ok(Solved {
+            parse_elapsed,
+            answers,
+        })
+    }
+}
+
+pub struct Day {
+    pub number: u8,
+    pub solver: &'static dyn Solver,
+}
+
+pub const DAYS: [Day; 8] = [
+    Day {
+        number: 1,
+        solver: &day1::Day1,
+    },
+    Day {
+        number: 2,
+        solver: &day2::Day2,
+    },
+    Day {
+        number: 3,
+        solver: &day3::Day3,
+    },
+    Day {
+        number: 4,
+        solver: &day4::Day4,
+    },
+    Day {
+        number: 5,
+        solver: &day5::Day5,
+    },
+    Day {
+        number: 6,
+        solver: &day6::Day6,
+    },
+    Day {
+        number: 7,
+        solver: &day7::Day7,
+    },
+    Day {
+        number: 8,
+        solver: &day8::Day8,
+    },
+];
+
+pub fn find_day(number: u8) -> Option<&'static Day> {
+    DAYS.iter().find(|day| day.number == number)
+}
+
+/// Answers `part` of a day's worked example, panicking if it fails to parse
+/// or solve.
+#[cfg(test)]
+pub(crate) fn solve_example(solver: &dyn Solver, input: &str, part: Part) -> String {
+    let mut diagnostics = Diagnostics::default();
+    let mut solved = solver.solve(input, &[part], &mut diagnostics).unwrap();
+
+    assert!(diagnostics.warnings.is_empty());
+    solved.answers.remove(0).answer.unwrap()
+}
@@ -0,0 +1,21 @@
+//! Helpers shared by the unit tests and the benches, which include this file
+//! with `#[path]` since they cannot see test-only items of the library.
+
+/// Deterministic pseudo-random numbers from a xorshift generator, so
+/// generated test inputs are the same on every run.
+pub struct Xorshift(u64);
+
+impl Xorshift {
+    /// A generator starting from `seed`, which must not be zero.
+    pub fn new(seed: u64) -> Xorshift {
+        Xorshift(seed)
+    }
+
+    /// The next number below `bound`.
+    pub fn below(&mut self, bound: usize) -> usize {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 % bound as u64) as usize
+    }
+}
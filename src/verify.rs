@@ -0,0 +1,177 @@
+use std::collections::HashMap;
+
+use crate::{
+    error::{Error, Result},
+    solution::Part,
+};
+
+/// Known-good answers, read from a manifest with one `<day> <part> <answer>`
+/// entry per line. Blank lines and lines starting with `#` are ignored.
+#[derive(Debug, Default)]
+pub struct Answers {
+    pub expected: HashMap<(u8, Part), String>,
+}
+
+impl Answers {
+    pub fn expected(&self, day: u8, part: Part) -> Option<&str> {
+        self.expected.get(&(day, part)).map(String::as_str)
+    }
+}
+
+pub fn read_answers(input: &str) -> Result<Answers> {
+    let mut answers = Answers::default();
+
+    for (line_index, line) in input.lines().enumerate() {
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+
+        let error =
+            |token: &str, message: &str| Error::parse_token(line_index + 1, line, token, message);
+
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        let (day, part, answer) = match tokens.as_slice() {
+            [day, part, answer] => (*day, *part, *answer),
+            [_, _, _, extra, ..] => return Err(error(extra, "Unexpected text after the answer")),
+            _ => {
+                return Err(Error::parse(
+                    line_index + 1,
+                    line,
+                    "Expected an entry like \"1 2 45000\"",
+                ))
+            }
+        };
+
+        let day_number = match day.parse::<u8>() {
+            Ok(day_number) if (1..=25).contains(&day_number) => day_number,
+            _ => return Err(error(day, "Day is not a number from 1 to 25")),
+        };
+        let part = match part {
+            "1" => Part::One,
+            "2" => Part::Two,
+            _ => return Err(error(part, "Part is not 1 or 2")),
+        };
+
+        if answers
+            .expected
+            .insert((day_number, part), answer.to_string())
+            .is_some()
+        {
+            return Err(Error::parse(
+                line_index + 1,
+                line,
+                format!("Day {day_number} part {part} already has an answer"),
+            ));
+        }
+    }
+
+    Ok(answers)
+}
+
+/// How a part's answer compares with the manifest.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Pass,
+    Fail,
+    /// The manifest has no answer for the part.
+    Missing,
+}
+
+impl Verdict {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Verdict::Pass => "pass",
+            Verdict::Fail => "fail",
+            Verdict::Missing => "missing",
+        }
+    }
+}
+
+/// Compares a part's `actual` answer with the `expected` one. A part with no
+/// answer, being unsolved or failing, fails when an answer is expected.
+pub fn verdict(expected: Option<&str>, actual: Option<&str>) -> Verdict {
+    match (expected, actual) {
+        (None, _) => Verdict::Missing,
+        (Some(expected), Some(actual)) if expected == actual => Verdict::Pass,
+        _ => Verdict::Fail,
+    }
+}
+
+/// Verdicts counted over a whole verification run.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Tally {
+    pub passed: usize,
+    pub failed: usize,
+    pub missing: usize,
+}
+
+impl Tally {
+    pub fn record(&mut self, verdict: Verdict) {
+        match verdict {
+            Verdict::Pass => self.passed += 1,
+            Verdict::Fail => self.failed += 1,
+            Verdict::Missing => self.missing += 1,
+        }
+    }
+
+    /// Whether the run succeeded: no part failed and, as a day whose input
+    /// cannot be read or parsed has no answers to compare, no error was met.
+    pub fn succeeded(&self, error_count: usize) -> bool {
+        self.failed == 0 && error_count == 0
+    }
+}
+
+impl std::fmt::Display for Tally {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{} passed, {} failed, {} missing",
+            self.passed, self.failed, self.missing
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_entries_skipping_comments_and_blank_lines() {
+        let answers = read_answers("# day part answer\n1 1 24000\n\n5 1 CMZ\n").unwrap();
+
+        assert_eq!(answers.expected(1, Part::One), Some("24000"));
+        assert_eq!(answers.expected(5, Part::One), Some("CMZ"));
+        assert_eq!(answers.expected(1, Part::Two), None);
+    }
+
+    #[test]
+    fn rejects_duplicate_and_malformed_entries() {
+        assert!(read_answers("1 1 24000\n1 1 24001\n").is_err());
+        assert!(read_answers("1 3 24000\n").is_err());
+        assert!(read_answers("26 1 24000\n").is_err());
+        assert!(read_answers("1 1\n").is_err());
+    }
+
+    #[test]
+    fn verdicts() {
+        assert_eq!(verdict(Some("15"), Some("15")), Verdict::Pass);
+        assert_eq!(verdict(Some("15"), Some("12")), Verdict::Fail);
+        assert_eq!(verdict(Some("15"), None), Verdict::Fail);
+        assert_eq!(verdict(None, Some("15")), Verdict::Missing);
+    }
+
+    #[test]
+    fn tally_fails_on_failed_parts_or_errors() {
+        let mut tally = Tally::default();
+        for verdict in [Verdict::Pass, Verdict::Missing, Verdict::Missing] {
+            tally.record(verdict);
+        }
+
+        assert_eq!(tally.to_string(), "1 passed, 0 failed, 2 missing");
+        assert!(tally.succeeded(0));
+        assert!(!tally.succeeded(1));
+
+        tally.record(Verdict::Fail);
+        assert!(!tally.succeeded(0));
+    }
+}